@@ -14,20 +14,164 @@ ink-examples repository.
 
 */
 
+#[cfg(any(
+    all(feature = "psp22", feature = "pallet-assets"),
+    all(feature = "psp22", feature = "zeitgeist"),
+    all(feature = "pallet-assets", feature = "zeitgeist"),
+))]
+compile_error!("Only one underlying asset backend feature can be enabled.");
+
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+#[cfg(any(feature = "pallet-assets", feature = "zeitgeist"))]
+use sp_runtime::MultiAddress;
+
+/// The errors a PSP22 token can return, both the vault's shares and a PSP22 underlying
+/// asset.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Returned for any error not covered by the other variants.
+    Custom(String),
+    /// Returned if not enough balance to fulfill a request is available.
+    InsufficientBalance,
+    /// Returned if not enough allowance to fulfill a request is available.
+    InsufficientAllowance,
+    /// Returned if the recipient's address is zero.
+    ZeroRecipientAddress,
+    /// Returned if the sender's address is zero.
+    ZeroSenderAddress,
+    /// Returned if a safe transfer check failed.
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 standard for fungible tokens, under which the vault's shares are traded.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Returns the total token supply.
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+
+    /// Returns the account balance for the specified `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u128;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> u128;
+
+    /// Transfers `value` tokens from the caller's account to account `to`.
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Allows `spender` to withdraw up to `value` tokens from the caller's account.
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error>;
+
+    /// Increases the allowance of `spender` over the caller's tokens by `delta_value`.
+    #[ink(message)]
+    fn increase_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+
+    /// Decreases the allowance of `spender` over the caller's tokens by `delta_value`.
+    #[ink(message)]
+    fn decrease_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+}
+
+/// The optional metadata of a PSP22 token.
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    /// Returns the token name.
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+
+    /// Returns the token symbol.
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+
+    /// Returns the token decimals.
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
+
 #[ink::contract]
-mod erc4626 {
-    use ink::storage::Mapping;
+pub mod erc4626 {
+    use self::asset::VaultAsset;
+    use crate::{PSP22Error, PSP22Metadata, PSP22};
+    use ink::prelude::{format, string::String, vec::Vec};
+    use ink::storage::{Lazy, Mapping};
+    use primitive_types::U256;
 
     /// A simple ERC-20 contract.
     #[ink(storage)]
-    #[derive(Default)]
     pub struct Erc4626 {
+        /// The underlying asset held by the vault.
+        asset: UnderlyingAsset,
         /// Total token supply.
         total_supply: Balance,
         /// Mapping from owner to number of owned token.
         balances: Mapping<AccountId, Balance>,
         /// The decimals of the asset being represented
         decimals: u8,
+        /// The number of decimals the shares have on top of the underlying asset,
+        /// which also sets the amount of virtual shares.
+        decimal_offset: u8,
+        /// The name of the vault's shares.
+        name: String,
+        /// The symbol of the vault's shares.
+        symbol: String,
+        /// The account holding the `DEFAULT_ADMIN` role.
+        admin: AccountId,
+        /// The account the `DEFAULT_ADMIN` role is being transferred to.
+        pending_admin: Option<AccountId>,
+        /// The accounts holding each of the other roles.
+        roles: Mapping<(RoleType, AccountId), ()>,
+        /// Whether `deposit` and `mint` are paused.
+        deposits_paused: bool,
+        /// Whether `withdraw` and `redeem` are paused.
+        withdrawals_paused: bool,
+        /// Whether share transfers are paused.
+        transfers_paused: bool,
+        /// The account that receives the fee shares.
+        fee_recipient: AccountId,
+        /// The yearly management fee, in basis points of the total assets.
+        management_fee_bps: u16,
+        /// The performance fee, in basis points of the gains above the high-water mark.
+        performance_fee_bps: u16,
+        /// The highest share price fees were accrued at, scaled by `SHARE_PRICE_PRECISION`.
+        high_water_mark: Balance,
+        /// The block timestamp fees were last accrued at.
+        last_fee_accrual: Timestamp,
+        /// The fees, in assets, that were accrued but were worth less than a share.
+        unpaid_fees: Balance,
+        /// The fee charged on deposits, in basis points of the assets deposited.
+        entry_fee_bps: u16,
+        /// The fee charged on withdrawals, in basis points of the assets withdrawn.
+        exit_fee_bps: u16,
+        /// Whether a guarded message is executing. Kept in its own storage cell, so it is
+        /// written before the vault calls out and seen by any call back into the vault.
+        locked: Lazy<bool>,
+        /// The total assets up to which the vault accepts deposits.
+        deposit_cap: Balance,
+        /// The assets up to which a single account may hold in the vault.
+        per_account_cap: Balance,
         /// Mapping of the token amount which an account is allowed to withdraw
         /// from another account.
         allowances: Mapping<(AccountId, AccountId), Balance>,
@@ -71,6 +215,79 @@ mod erc4626 {
         shares: Balance,
     }
 
+    /// Event emitted when `role` is granted to `account`.
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleType,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    /// Event emitted when `role` is revoked from `account`.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleType,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    /// Event emitted when the admin starts transferring the `DEFAULT_ADMIN` role.
+    #[ink(event)]
+    pub struct AdminTransferStarted {
+        #[ink(topic)]
+        admin: AccountId,
+        #[ink(topic)]
+        pending_admin: AccountId,
+    }
+
+    /// Event emitted when `target` is paused by `account`.
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        target: PauseTarget,
+        account: AccountId,
+    }
+
+    /// Event emitted when `target` is unpaused by `account`.
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        target: PauseTarget,
+        account: AccountId,
+    }
+
+    /// Event emitted when fee shares are minted to the fee recipient.
+    #[ink(event)]
+    pub struct FeesAccrued {
+        #[ink(topic)]
+        recipient: AccountId,
+        management_fee: Balance,
+        performance_fee: Balance,
+        shares: Balance,
+    }
+
+    /// Event emitted when the fees or the fee recipient of the vault change.
+    #[ink(event)]
+    pub struct FeesUpdated {
+        #[ink(topic)]
+        fee_recipient: AccountId,
+        management_fee_bps: u16,
+        performance_fee_bps: u16,
+        entry_fee_bps: u16,
+        exit_fee_bps: u16,
+    }
+
+    /// Event emitted when the deposit caps of the vault change.
+    #[ink(event)]
+    pub struct DepositCapsUpdated {
+        deposit_cap: Balance,
+        per_account_cap: Balance,
+    }
+
     /// The ERC-20 error types.
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -79,6 +296,8 @@ mod erc4626 {
         InsufficientBalance,
         /// Returned if not enough allowance to fulfill a request is available.
         InsufficientAllowance,
+        /// Returned when an allowance would be decreased below zero.
+        AllowanceBelowZero,
         /// Returned when making a deposit, and the deposit is too high.
         ExceededMaxDeposit,
         /// Returned when minting, and the mint is too high.
@@ -87,123 +306,769 @@ mod erc4626 {
         ExceededMaxWithdraw,
         /// Returned when redeeming, and the redeem is too high.
         ExceededMaxRedeem,
+        /// Returned when a deposit would mint fewer shares than the minimum requested.
+        InsufficientSharesOut,
+        /// Returned when a mint would take more assets than the maximum requested.
+        ExceededMaxAssetsIn,
+        /// Returned when a withdrawal would burn more shares than the maximum requested.
+        ExceededMaxSharesIn,
+        /// Returned when a redemption would send fewer assets than the minimum requested.
+        InsufficientAssetsOut,
+        /// Returned when the caller is not allowed to call a message.
+        Unauthorized,
+        /// Returned when the requested operation is paused.
+        Paused,
+        /// Returned when a fee is higher than `MAX_BPS`.
+        InvalidFee,
+        /// Returned when a guarded message is called again before it returns.
+        Reentrancy,
+        /// Returned when the `DEFAULT_ADMIN` role is granted, revoked or renounced
+        /// instead of being transferred with `transfer_admin`.
+        InvalidRole,
+        /// Returned when an arithmetic operation overflows or underflows.
+        ArithmeticOverflow,
+        /// Returned when the transfer of the underlying asset fails.
+        AssetTransferFailed,
+        /// Returned when the balance of the underlying asset held by the vault cannot be
+        /// read.
+        AssetQueryFailed,
+        /// Returned when the vault is created over an asset its backend cannot hold.
+        UnsupportedAsset,
     }
 
     /// The ERC-20 result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InsufficientBalance => PSP22Error::InsufficientBalance,
+                Error::InsufficientAllowance | Error::AllowanceBelowZero => {
+                    PSP22Error::InsufficientAllowance
+                }
+                error => PSP22Error::Custom(format!("{error:?}")),
+            }
+        }
+    }
+
+    /// The identifier of a role.
+    pub type RoleType = u32;
+
+    /// Manages the other roles and the settings of the vault. Held by a single account
+    /// and only moved with `transfer_admin` and `accept_admin`.
+    pub const DEFAULT_ADMIN: RoleType = 0;
+    /// Pauses and unpauses the vault.
+    pub const PAUSER: RoleType = ink::selector_id!("PAUSER");
+    /// Changes the fees of the vault.
+    pub const FEE_MANAGER: RoleType = ink::selector_id!("FEE_MANAGER");
+    /// Changes how the vault invests its assets.
+    pub const STRATEGIST: RoleType = ink::selector_id!("STRATEGIST");
+
+    /// The operations of the vault that can be paused separately.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PauseTarget {
+        /// `deposit` and `mint`.
+        Deposits,
+        /// `withdraw` and `redeem`.
+        Withdrawals,
+        /// `transfer` and `transfer_from`.
+        Transfers,
+    }
+
+    /// The denominator of fees expressed in basis points.
+    pub const MAX_BPS: u16 = 10_000;
+    /// The period the management fee is charged over, a year of 365 days in milliseconds.
+    pub const YEAR_MILLIS: u64 = 31_536_000_000;
+    /// The scale of the share price tracked by the high-water mark.
+    pub const SHARE_PRICE_PRECISION: Balance = 1_000_000_000_000_000_000;
+
+    /// The fees owed to the fee recipient since the last accrual.
+    struct PendingFees {
+        /// The management fee, in assets.
+        management_fee: Balance,
+        /// The performance fee, in assets.
+        performance_fee: Balance,
+        /// The shares to mint to the fee recipient to pay both fees and the unpaid fees.
+        shares: Balance,
+        /// The fees, in assets, left unpaid once the fee shares are minted.
+        unpaid_fees: Balance,
+        /// The high-water mark once the fee shares are minted.
+        high_water_mark: Balance,
+    }
+
+    /// The account that receives the shares of the seed deposit. Nobody holds its key,
+    /// so the seed deposit can never be withdrawn.
+    pub const DEAD_ACCOUNT: [u8; 32] = [0; 32];
+
+    /// The rounding direction used when converting between assets and shares.
+    ///
+    /// ERC-4626 requires every conversion to round in favour of the vault.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Rounding {
+        /// Rounds towards zero.
+        Floor,
+        /// Rounds away from zero.
+        Ceil,
+    }
+
+    /// Returns how far `used` is below `cap`, where a cap of `Balance::MAX` is unlimited.
+    fn cap_headroom(cap: Balance, used: Balance) -> Balance {
+        if cap == Balance::MAX {
+            return Balance::MAX;
+        }
+        cap.saturating_sub(used)
+    }
+
+    /// Returns the fee to add to `assets` for a fee of `fee_bps`, rounded up.
+    fn fee_on_raw(assets: Balance, fee_bps: u16) -> Result<Balance> {
+        mul_div(assets, fee_bps.into(), MAX_BPS.into(), Rounding::Ceil)
+    }
+
+    /// Returns the fee included in `assets` for a fee of `fee_bps`, rounded up.
+    fn fee_on_total(assets: Balance, fee_bps: u16) -> Result<Balance> {
+        mul_div(
+            assets,
+            fee_bps.into(),
+            Balance::from(fee_bps) + Balance::from(MAX_BPS),
+            Rounding::Ceil,
+        )
+    }
+
+    /// Returns the price of a share in `total_assets` over `supply` shares, scaled by
+    /// `SHARE_PRICE_PRECISION`, or `Balance::MAX` if the price does not fit.
+    fn share_price(total_assets: Balance, supply: Balance) -> Balance {
+        mul_div(SHARE_PRICE_PRECISION, total_assets, supply, Rounding::Floor)
+            .unwrap_or(Balance::MAX)
+    }
+
+    /// Calculates `a * b / denominator` with a 256-bit intermediate, so the product
+    /// cannot overflow before the division.
+    ///
+    /// Returns `ArithmeticOverflow` if `denominator` is zero or the result does not fit
+    /// into a `Balance`.
+    pub fn mul_div(
+        a: Balance,
+        b: Balance,
+        denominator: Balance,
+        rounding: Rounding,
+    ) -> Result<Balance> {
+        if denominator == 0 {
+            return Err(Error::ArithmeticOverflow);
+        }
+
+        let (quotient, remainder) = (U256::from(a) * U256::from(b)).div_mod(denominator.into());
+        let quotient = match rounding {
+            Rounding::Ceil if !remainder.is_zero() => quotient + 1,
+            _ => quotient,
+        };
+        Balance::try_from(quotient).map_err(|_| Error::ArithmeticOverflow)
+    }
+
+    /// The backend of the underlying asset, selected by cargo feature.
+    #[cfg(feature = "psp22")]
+    pub type UnderlyingAsset = asset::Psp22Asset;
+    /// The backend of the underlying asset, selected by cargo feature.
+    #[cfg(feature = "pallet-assets")]
+    pub type UnderlyingAsset = asset::PalletAsset;
+    /// The backend of the underlying asset, selected by cargo feature.
+    #[cfg(feature = "zeitgeist")]
+    pub type UnderlyingAsset = asset::ZeitgeistCurrency;
+    /// The backend of the underlying asset, selected by cargo feature.
+    #[cfg(not(any(feature = "psp22", feature = "pallet-assets", feature = "zeitgeist")))]
+    pub type UnderlyingAsset = asset::NativeAsset;
+
+    /// The identifier of the underlying asset.
+    pub type AssetId = <UnderlyingAsset as VaultAsset>::Id;
+
+    /// Backends that move and count the underlying asset of the vault.
+    ///
+    /// @dev Implement `VaultAsset` to support another kind of underlying asset.
+    pub mod asset {
+        use super::{AccountId, Balance, Environment, Error, Result};
+        #[cfg(feature = "psp22")]
+        use crate::PSP22Error;
+        #[cfg(feature = "psp22")]
+        use ink::env::call::{build_call, ExecutionInput, Selector};
+        #[cfg(any(feature = "pallet-assets", feature = "zeitgeist"))]
+        use ink::env::chain_extension::{ChainExtensionMethod, FromStatusCode};
+        #[cfg(any(feature = "pallet-assets", feature = "zeitgeist"))]
+        use ink::prelude::format;
+        use ink::prelude::string::String;
+        #[cfg(feature = "psp22")]
+        use ink::prelude::vec::Vec;
+
+        /// Moves and counts the underlying asset of the vault.
+        pub trait VaultAsset {
+            /// The identifier of the underlying asset.
+            type Id;
+
+            /// Creates the backend for the asset identified by `id`.
+            ///
+            /// Returns `UnsupportedAsset` error if the backend cannot hold the asset.
+            fn from_id(id: Self::Id) -> Result<Self>
+            where
+                Self: Sized;
+
+            /// Returns the identifier of the underlying asset.
+            fn id(&self) -> Self::Id;
+
+            /// Transfers `amount` of the underlying asset from `from` into the vault.
+            fn pull(&self, from: AccountId, amount: Balance) -> Result<()>;
+
+            /// Transfers `amount` of the underlying asset from the vault to `to`.
+            fn push(&self, to: AccountId, amount: Balance) -> Result<()>;
+
+            /// Returns the amount of the underlying asset held by the vault.
+            fn balance_of_vault(&self) -> Result<Balance>;
+
+            /// Returns the symbol of the underlying asset, from which the name and
+            /// symbol of the vault's shares are derived.
+            fn symbol(&self) -> String;
+        }
+
+        /// The status code of a chain extension of the runtime, zero on success.
+        #[cfg(any(feature = "pallet-assets", feature = "zeitgeist"))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct ExtensionStatusCode(pub u32);
+
+        #[cfg(any(feature = "pallet-assets", feature = "zeitgeist"))]
+        impl FromStatusCode for ExtensionStatusCode {
+            fn from_status_code(status_code: u32) -> core::result::Result<(), Self> {
+                match status_code {
+                    0 => Ok(()),
+                    status_code => Err(Self(status_code)),
+                }
+            }
+        }
+
+        /// The symbol of the native currency of the chain.
+        ///
+        /// @dev Replace with the symbol of the native currency of your chain.
+        #[cfg(not(any(feature = "psp22", feature = "pallet-assets")))]
+        pub const NATIVE_SYMBOL: &str = "UNIT";
+
+        /// The native currency of the chain, sent as value with `deposit` and `mint`.
+        #[cfg(not(any(feature = "psp22", feature = "pallet-assets")))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+        #[cfg_attr(
+            feature = "std",
+            derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+        )]
+        pub struct NativeAsset;
+
+        #[cfg(not(any(feature = "psp22", feature = "pallet-assets")))]
+        impl VaultAsset for NativeAsset {
+            type Id = NativeAsset;
+
+            fn from_id(id: Self::Id) -> Result<Self> {
+                Ok(id)
+            }
+
+            fn id(&self) -> Self::Id {
+                *self
+            }
+
+            fn pull(&self, _from: AccountId, amount: Balance) -> Result<()> {
+                // Ensures that value is being transferred into the vault
+                if amount != ink::env::transferred_value::<Environment>() {
+                    return Err(Error::InsufficientAllowance);
+                }
+                Ok(())
+            }
+
+            fn push(&self, to: AccountId, amount: Balance) -> Result<()> {
+                // The runtime refuses to send away the existential deposit of the vault
+                let available = ink::env::balance::<Environment>()
+                    .saturating_sub(ink::env::minimum_balance::<Environment>());
+                if amount > available {
+                    return Err(Error::AssetTransferFailed);
+                }
+                ink::env::transfer::<Environment>(to, amount)
+                    .map_err(|_| Error::AssetTransferFailed)
+            }
+
+            fn balance_of_vault(&self) -> Result<Balance> {
+                // The value sent with the current call is not yet managed by the vault,
+                // and the existential deposit can never be paid out
+                Ok(ink::env::balance::<Environment>()
+                    .saturating_sub(ink::env::transferred_value::<Environment>())
+                    .saturating_sub(ink::env::minimum_balance::<Environment>()))
+            }
+
+            fn symbol(&self) -> String {
+                String::from(NATIVE_SYMBOL)
+            }
+        }
+
+        /// The ID of the chain extension function returning the `AssetManager` balance
+        /// of an account.
+        ///
+        /// The Zeitgeist runtime has no such chain extension, so this is a placeholder.
+        ///
+        /// @dev Replace with the function ID of the chain extension of your runtime.
+        #[cfg(feature = "zeitgeist")]
+        pub const CURRENCY_BALANCE_OF_FUNC_ID: u32 = 0x0028_0001;
+
+        /// The ID of the chain extension function transferring an `AssetManager`
+        /// currency from the caller of the vault to the vault.
+        ///
+        /// The runtime must only move the currency of the account calling the vault, so
+        /// a deposit can only ever spend the depositor's own funds. The Zeitgeist runtime
+        /// has no such chain extension, so this is a placeholder.
+        ///
+        /// @dev Replace with the function ID of the chain extension of your runtime.
+        #[cfg(feature = "zeitgeist")]
+        pub const CURRENCY_TRANSFER_FROM_CALLER_FUNC_ID: u32 = 0x0028_0002;
+
+        /// A currency of the `AssetManager` pallet of Zeitgeist.
+        ///
+        /// ZTG is the native currency, sent as value with `deposit` and `mint`. Any other
+        /// currency is sent to withdrawers through `call_runtime`, while deposits and the
+        /// vault's balance go through a chain extension the runtime must provide.
+        #[cfg(feature = "zeitgeist")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+        #[cfg_attr(
+            feature = "std",
+            derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+        )]
+        pub struct ZeitgeistCurrency {
+            /// The currency held by the vault.
+            currency: crate::ZeitgeistAsset,
+        }
+
+        #[cfg(feature = "zeitgeist")]
+        impl VaultAsset for ZeitgeistCurrency {
+            type Id = crate::ZeitgeistAsset;
+
+            fn from_id(currency: Self::Id) -> Result<Self> {
+                // Combinatorial outcomes are not tradable on the chain yet
+                if currency == crate::ZeitgeistAsset::CombinatorialOutcome {
+                    return Err(Error::UnsupportedAsset);
+                }
+                Ok(Self { currency })
+            }
+
+            fn id(&self) -> Self::Id {
+                self.currency
+            }
+
+            fn pull(&self, from: AccountId, amount: Balance) -> Result<()> {
+                if self.currency == crate::ZeitgeistAsset::Ztg {
+                    return NativeAsset.pull(from, amount);
+                }
+                // Native value sent along would be stuck in the vault
+                if ink::env::transferred_value::<Environment>() != 0 {
+                    return Err(Error::AssetTransferFailed);
+                }
+                // The chain extension only moves the currency of the vault's caller
+                if from != ink::env::caller::<Environment>() {
+                    return Err(Error::AssetTransferFailed);
+                }
+                ChainExtensionMethod::build(CURRENCY_TRANSFER_FROM_CALLER_FUNC_ID)
+                    .input::<(crate::ZeitgeistAsset, Balance)>()
+                    .output::<(), false>()
+                    .handle_error_code::<ExtensionStatusCode>()
+                    .call(&(self.currency, amount))
+                    .map_err(|_| Error::AssetTransferFailed)
+            }
+
+            fn push(&self, to: AccountId, amount: Balance) -> Result<()> {
+                if self.currency == crate::ZeitgeistAsset::Ztg {
+                    return NativeAsset.push(to, amount);
+                }
+                ink::env::call_runtime::<Environment, _>(&crate::RuntimeCall::AssetManager(
+                    crate::AssetManagerCall::Transfer {
+                        dest: to.into(),
+                        currency_id: self.currency,
+                        amount,
+                    },
+                ))
+                .map_err(|_| Error::AssetTransferFailed)
+            }
+
+            fn balance_of_vault(&self) -> Result<Balance> {
+                if self.currency == crate::ZeitgeistAsset::Ztg {
+                    return NativeAsset.balance_of_vault();
+                }
+                ChainExtensionMethod::build(CURRENCY_BALANCE_OF_FUNC_ID)
+                    .input::<(crate::ZeitgeistAsset, AccountId)>()
+                    .output::<Balance, false>()
+                    .handle_error_code::<ExtensionStatusCode>()
+                    .call(&(self.currency, ink::env::account_id::<Environment>()))
+                    .map_err(|_| Error::AssetQueryFailed)
+            }
+
+            fn symbol(&self) -> String {
+                use crate::{ScalarPosition, ZeitgeistAsset};
+                match self.currency {
+                    ZeitgeistAsset::CategoricalOutcome(market_id, index) => {
+                        format!("CAT-{market_id}-{index}")
+                    }
+                    ZeitgeistAsset::ScalarOutcome(market_id, ScalarPosition::Long) => {
+                        format!("SCALAR-{market_id}-LONG")
+                    }
+                    ZeitgeistAsset::ScalarOutcome(market_id, ScalarPosition::Short) => {
+                        format!("SCALAR-{market_id}-SHORT")
+                    }
+                    ZeitgeistAsset::CombinatorialOutcome => String::from("COMBINATORIAL"),
+                    ZeitgeistAsset::PoolShare(pool_id) => format!("POOL-{pool_id}"),
+                    ZeitgeistAsset::Ztg => String::from("ZTG"),
+                    ZeitgeistAsset::ForeignAsset(id) => format!("FOREIGN-{id}"),
+                }
+            }
+        }
+
+        /// A PSP22 contract, moved through cross-contract calls.
+        #[cfg(feature = "psp22")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+        #[cfg_attr(
+            feature = "std",
+            derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+        )]
+        pub struct Psp22Asset {
+            /// The address of the PSP22 contract.
+            token: AccountId,
+        }
+
+        #[cfg(feature = "psp22")]
+        impl Psp22Asset {
+            /// Transfers `value` of the token from `from` to `to`, spending the
+            /// allowance `from` has given to the vault.
+            fn transfer_from(&self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
+                build_call::<Environment>()
+                    .call(self.token)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                            "PSP22::transfer_from"
+                        )))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value)
+                        .push_arg(Vec::<u8>::new()),
+                    )
+                    .returns::<core::result::Result<(), PSP22Error>>()
+                    .try_invoke()
+                    .map_err(|_| Error::AssetTransferFailed)?
+                    .map_err(|_| Error::AssetTransferFailed)?
+                    .map_err(|_| Error::AssetTransferFailed)
+            }
+
+            /// Transfers `value` of the token from the vault to `to`.
+            fn transfer(&self, to: AccountId, value: Balance) -> Result<()> {
+                build_call::<Environment>()
+                    .call(self.token)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                            .push_arg(to)
+                            .push_arg(value)
+                            .push_arg(Vec::<u8>::new()),
+                    )
+                    .returns::<core::result::Result<(), PSP22Error>>()
+                    .try_invoke()
+                    .map_err(|_| Error::AssetTransferFailed)?
+                    .map_err(|_| Error::AssetTransferFailed)?
+                    .map_err(|_| Error::AssetTransferFailed)
+            }
+        }
+
+        #[cfg(feature = "psp22")]
+        impl VaultAsset for Psp22Asset {
+            type Id = AccountId;
+
+            fn from_id(token: Self::Id) -> Result<Self> {
+                Ok(Self { token })
+            }
+
+            fn id(&self) -> Self::Id {
+                self.token
+            }
+
+            fn pull(&self, from: AccountId, amount: Balance) -> Result<()> {
+                // Native value sent along would be stuck in the vault
+                if ink::env::transferred_value::<Environment>() != 0 {
+                    return Err(Error::AssetTransferFailed);
+                }
+                self.transfer_from(from, ink::env::account_id::<Environment>(), amount)
+            }
+
+            fn push(&self, to: AccountId, amount: Balance) -> Result<()> {
+                self.transfer(to, amount)
+            }
+
+            fn balance_of_vault(&self) -> Result<Balance> {
+                build_call::<Environment>()
+                    .call(self.token)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                            "PSP22::balance_of"
+                        )))
+                        .push_arg(ink::env::account_id::<Environment>()),
+                    )
+                    .returns::<Balance>()
+                    .try_invoke()
+                    .map_err(|_| Error::AssetQueryFailed)?
+                    .map_err(|_| Error::AssetQueryFailed)
+            }
+
+            fn symbol(&self) -> String {
+                // Tokens without a symbol are named after the standard
+                build_call::<Environment>()
+                    .call(self.token)
+                    .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "PSP22Metadata::token_symbol"
+                    ))))
+                    .returns::<Option<String>>()
+                    .try_invoke()
+                    .ok()
+                    .and_then(|result| result.ok())
+                    .flatten()
+                    .unwrap_or_else(|| String::from("PSP22"))
+            }
+        }
+
+        /// The ID of the chain extension function returning the `pallet-assets` balance
+        /// of an account.
+        ///
+        /// @dev Replace with the function ID of the chain extension of your runtime.
+        #[cfg(feature = "pallet-assets")]
+        pub const ASSETS_BALANCE_OF_FUNC_ID: u32 = 0x0402_0001;
+
+        /// An asset of `pallet-assets`, moved through `call_runtime`.
+        #[cfg(feature = "pallet-assets")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+        #[cfg_attr(
+            feature = "std",
+            derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+        )]
+        pub struct PalletAsset {
+            /// The index of `pallet-assets` in the `construct_runtime!` block.
+            pub pallet_index: u8,
+            /// The ID of the asset within `pallet-assets`.
+            pub asset_id: u32,
+        }
+
+        #[cfg(feature = "pallet-assets")]
+        impl PalletAsset {
+            /// Dispatches `call` to `pallet-assets` with the vault as origin.
+            fn dispatch(&self, call: crate::AssetsCall) -> Result<()> {
+                ink::env::call_runtime::<Environment, _>(&crate::RuntimeCall {
+                    pallet_index: self.pallet_index,
+                    call,
+                })
+                .map_err(|_| Error::AssetTransferFailed)
+            }
+        }
+
+        #[cfg(feature = "pallet-assets")]
+        impl VaultAsset for PalletAsset {
+            type Id = PalletAsset;
+
+            fn from_id(id: Self::Id) -> Result<Self> {
+                Ok(id)
+            }
+
+            fn id(&self) -> Self::Id {
+                *self
+            }
+
+            fn pull(&self, from: AccountId, amount: Balance) -> Result<()> {
+                // Native value sent along would be stuck in the vault
+                if ink::env::transferred_value::<Environment>() != 0 {
+                    return Err(Error::AssetTransferFailed);
+                }
+                // Spends the approval `from` has given to the vault
+                self.dispatch(crate::AssetsCall::TransferApproved {
+                    id: self.asset_id,
+                    owner: from.into(),
+                    destination: ink::env::account_id::<Environment>().into(),
+                    amount,
+                })
+            }
+
+            fn push(&self, to: AccountId, amount: Balance) -> Result<()> {
+                self.dispatch(crate::AssetsCall::Transfer {
+                    id: self.asset_id,
+                    target: to.into(),
+                    amount,
+                })
+            }
+
+            fn balance_of_vault(&self) -> Result<Balance> {
+                ChainExtensionMethod::build(ASSETS_BALANCE_OF_FUNC_ID)
+                    .input::<(u32, AccountId)>()
+                    .output::<Balance, false>()
+                    .handle_error_code::<ExtensionStatusCode>()
+                    .call(&(self.asset_id, ink::env::account_id::<Environment>()))
+                    .map_err(|_| Error::AssetQueryFailed)
+            }
+
+            fn symbol(&self) -> String {
+                format!("ASSET-{}", self.asset_id)
+            }
+        }
+    }
+
     impl Erc4626 {
-        /// Creates a new ERC-20 contract with the specified initial supply.
-        #[ink(constructor)]
-        pub fn new(total_supply: Balance, decimals: u8) -> Self {
-            let mut balances = Mapping::default();
-            let caller = Self::env().caller();
-            balances.insert(caller, &total_supply);
-            Self::env().emit_event(Transfer {
-                from: None,
-                to: Some(caller),
-                value: total_supply,
-            });
-            Self {
-                total_supply,
-                balances,
+        /// Creates a new, empty ERC-4626 vault over `asset`.
+        ///
+        /// `decimal_offset` adds `10^decimal_offset` virtual shares to the share price,
+        /// which makes inflating the price with donations unprofitable.
+        ///
+        /// If `seed_assets` is non-zero, they are deposited by the caller and their
+        /// shares are minted to the dead account, so the vault is never empty.
+        #[ink(constructor, payable)]
+        pub fn new(
+            asset: AssetId,
+            decimals: u8,
+            decimal_offset: u8,
+            seed_assets: Balance,
+        ) -> Result<Self> {
+            let asset = UnderlyingAsset::from_id(asset)?;
+            let asset_symbol = asset.symbol();
+            let mut vault = Self {
+                asset,
+                total_supply: 0,
+                balances: Mapping::default(),
                 decimals,
+                decimal_offset,
+                name: format!("Vault {asset_symbol}"),
+                symbol: format!("v{asset_symbol}"),
+                admin: Self::env().caller(),
+                pending_admin: None,
+                roles: Mapping::default(),
+                deposits_paused: false,
+                withdrawals_paused: false,
+                transfers_paused: false,
+                fee_recipient: Self::env().caller(),
+                management_fee_bps: 0,
+                performance_fee_bps: 0,
+                high_water_mark: 0,
+                last_fee_accrual: Self::env().block_timestamp(),
+                unpaid_fees: 0,
+                entry_fee_bps: 0,
+                exit_fee_bps: 0,
+                locked: Lazy::new(),
+                deposit_cap: Balance::MAX,
+                per_account_cap: Balance::MAX,
                 allowances: Default::default(),
+            };
+            let total_assets = vault.total_assets()?;
+            vault.do_accrue_fees(total_assets)?;
+            if seed_assets > 0 {
+                let shares = vault.preview_deposit_impl(seed_assets, total_assets)?;
+                vault.real_deposit(
+                    Self::env().caller(),
+                    AccountId::from(DEAD_ACCOUNT),
+                    seed_assets,
+                    shares,
+                    total_assets,
+                )?;
             }
+            Ok(vault)
         }
 
         // region: Read Only
 
-        // @dev Replace with the address/multilocation of the underlying token
-        // used for the vault for accounting, depositing, withdrawing.
+        /// Returns the identifier of the underlying asset used for the vault for
+        /// accounting, depositing, withdrawing.
         #[ink(message)]
-        pub fn asset(&self) {
-            todo!();
+        pub fn asset(&self) -> AssetId {
+            self.asset.id()
         }
 
-        // @dev Replace with the total amount of underlying assets held by the vault.
+        /// Returns the total amount of underlying assets managed by the vault.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AssetQueryFailed` error if the balance of the vault cannot be read.
         #[ink(message)]
-        pub fn total_assets(&self) {
-            todo!();
+        pub fn total_assets(&self) -> Result<Balance> {
+            self.asset.balance_of_vault()
         }
 
         /// Returns the amount of shares that would be exchanged by the vault for the
         /// amount of assets provided.
+        ///
+        /// Shares are priced proportionally to `total_assets` and `total_supply`, so
+        /// the share price follows yield and losses of the vault.
         #[ink(message)]
-        pub fn convert_to_shares(&self, assets: Balance) -> Balance {
-            assets * 10_u128.pow(self.decimal_offset().into())
+        pub fn convert_to_shares(&self, assets: Balance) -> Result<Balance> {
+            self.convert_to_shares_rounded(assets, Rounding::Floor, self.total_assets()?)
         }
 
         /// returns the amount of assets that would be exchanged by the vault for the
         /// amount of shares provided.
         #[ink(message)]
-        pub fn convert_to_assets(&self, shares: Balance) -> Balance {
-            shares as u128 / 10_u128.pow(self.decimal_offset().into())
+        pub fn convert_to_assets(&self, shares: Balance) -> Result<Balance> {
+            self.convert_to_assets_rounded(shares, Rounding::Floor, self.total_assets()?)
         }
 
         /// The maximum amount of underlying assets that can be deposited in a single
         /// deposit call by the receiver.
         #[ink(message)]
-        pub fn max_deposit(&self, _depositor: AccountId) -> Balance {
-            // @dev You can change this function to change the maximum amount that
-            // can be deposited at a time
-            Balance::from(u128::MAX)
+        pub fn max_deposit(&self, receiver: AccountId) -> Balance {
+            let Ok(total_assets) = self.total_assets() else {
+                return 0;
+            };
+            self.max_deposit_impl(receiver, total_assets)
         }
 
         /// Allows users to simulate the effects of their deposit at the current block.
         #[ink(message)]
-        pub fn preview_deposit(&self, assets: Balance) -> Balance {
-            // @dev You can change this function to change the calculation of depositing
-            self.convert_to_shares(assets)
+        pub fn preview_deposit(&self, assets: Balance) -> Result<Balance> {
+            self.preview_deposit_impl(assets, self.total_assets()?)
         }
 
         /// Returns the maximum amount of shares that can be minted in a single mint
         /// call by the receiver.
         #[ink(message)]
-        pub fn max_mint(&self, _receiver: AccountId) -> Balance {
-            // @dev You can change this function to change the maximum amount of shares
-            // that can be minted at a time
-            Balance::from(u128::MAX)
+        pub fn max_mint(&self, receiver: AccountId) -> Balance {
+            let Ok(total_assets) = self.total_assets() else {
+                return 0;
+            };
+            self.max_mint_impl(receiver, total_assets)
         }
 
         /// Returns the maximum amount of shares that can be minted in a single mint
         /// call by the receiver.
         #[ink(message)]
-        pub fn preview_mint(&self, shares: Balance) -> Balance {
-            // @dev You can change this function to change the calculation of minting
-            self.convert_to_assets(shares)
+        pub fn preview_mint(&self, shares: Balance) -> Result<Balance> {
+            self.preview_mint_impl(shares, self.total_assets()?)
         }
 
-        /// Mints exactly shares vault shares to receiver by depositing assets of
-        /// underlying tokens.
+        /// Returns the maximum amount of underlying assets that can be withdrawn from the
+        /// owner balance through a withdraw call.
         #[ink(message)]
-        pub fn max_withdraw(&self, _owner: AccountId) -> Balance {
-            // @dev You can change this function to change the maximum amount of assets
-            // that can be withdrawn at a time
-            Balance::from(u128::MAX)
+        pub fn max_withdraw(&self, owner: AccountId) -> Balance {
+            let Ok(total_assets) = self.total_assets() else {
+                return 0;
+            };
+            self.max_withdraw_impl(owner, total_assets)
         }
 
         /// Allows users to simulate the effects of their withdrawal at the current block.
         #[ink(message)]
-        pub fn preview_withdraw(&self, assets: Balance) -> Balance {
-            // @dev You can change this function to change the calculation of withdrawing
-            self.convert_to_shares(assets)
+        pub fn preview_withdraw(&self, assets: Balance) -> Result<Balance> {
+            self.preview_withdraw_impl(assets, self.total_assets()?)
         }
 
         /// Returns the maximum amount of shares that can be redeemed from the owner balance
         /// through a redeem call.
         #[ink(message)]
-        pub fn max_redeem(&self, _owner: AccountId) -> Balance {
-            // @dev You can change this function to change the maximum amount of assets
+        pub fn max_redeem(&self, owner: AccountId) -> Balance {
+            // @dev You can change this function to change the maximum amount of shares
             // that can be redeemed at a time
-            Balance::from(u128::MAX)
+            if self.withdrawals_paused {
+                return 0;
+            }
+            self.balance_of_impl(&owner)
         }
 
         /// Allows users to simulate the effects of their redemption at the current block.
         #[ink(message)]
-        pub fn preview_redeem(&self, shares: Balance) -> Balance {
-            // @dev You can change this function to change the calculation of redeeming
-            self.convert_to_shares(shares)
+        pub fn preview_redeem(&self, shares: Balance) -> Result<Balance> {
+            self.preview_redeem_impl(shares, self.total_assets()?)
         }
 
         /// Returns the total token supply.
@@ -220,14 +1085,116 @@ mod erc4626 {
             self.balance_of_impl(&owner)
         }
 
+        /// Returns the total assets up to which the vault accepts deposits.
+        #[ink(message)]
+        pub fn deposit_cap(&self) -> Balance {
+            self.deposit_cap
+        }
+
+        /// Returns the assets up to which a single account may hold in the vault.
+        #[ink(message)]
+        pub fn per_account_cap(&self) -> Balance {
+            self.per_account_cap
+        }
+
+        /// Returns `true` if `account` holds `role`.
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleType, account: AccountId) -> bool {
+            if role == DEFAULT_ADMIN {
+                return account == self.admin;
+            }
+            self.roles.contains((role, account))
+        }
+
+        /// Returns the account holding the `DEFAULT_ADMIN` role.
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Returns the account that can accept the `DEFAULT_ADMIN` role, if any.
+        #[ink(message)]
+        pub fn pending_admin(&self) -> Option<AccountId> {
+            self.pending_admin
+        }
+
+        /// Returns the account that receives the fee shares.
+        #[ink(message)]
+        pub fn fee_recipient(&self) -> AccountId {
+            self.fee_recipient
+        }
+
+        /// Returns the yearly management fee, in basis points of the total assets.
+        #[ink(message)]
+        pub fn management_fee(&self) -> u16 {
+            self.management_fee_bps
+        }
+
+        /// Returns the performance fee, in basis points of the gains above the
+        /// high-water mark.
+        #[ink(message)]
+        pub fn performance_fee(&self) -> u16 {
+            self.performance_fee_bps
+        }
+
+        /// Returns the fee charged on deposits, in basis points of the assets deposited.
+        #[ink(message)]
+        pub fn entry_fee(&self) -> u16 {
+            self.entry_fee_bps
+        }
+
+        /// Returns the fee charged on withdrawals, in basis points of the assets
+        /// withdrawn.
+        #[ink(message)]
+        pub fn exit_fee(&self) -> u16 {
+            self.exit_fee_bps
+        }
+
+        /// Returns the highest share price fees were accrued at, scaled by
+        /// `SHARE_PRICE_PRECISION`.
+        #[ink(message)]
+        pub fn high_water_mark(&self) -> Balance {
+            self.high_water_mark
+        }
+
+        /// Returns the fees, in assets, that were accrued but not paid as shares yet.
+        #[ink(message)]
+        pub fn unpaid_fees(&self) -> Balance {
+            self.unpaid_fees
+        }
+
+        /// Returns `true` if `target` is paused.
+        #[ink(message)]
+        pub fn is_paused(&self, target: PauseTarget) -> bool {
+            match target {
+                PauseTarget::Deposits => self.deposits_paused,
+                PauseTarget::Withdrawals => self.withdrawals_paused,
+                PauseTarget::Transfers => self.transfers_paused,
+            }
+        }
+
+        /// Returns the name of the vault's shares, `Vault <asset symbol>`.
+        #[ink(message)]
+        pub fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Returns the symbol of the vault's shares, `v<asset symbol>`.
+        #[ink(message)]
+        pub fn symbol(&self) -> String {
+            self.symbol.clone()
+        }
+
         /// Returns the decimals of this ERC20 asset.
+        #[ink(message)]
         pub fn decimals(&self) -> u8 {
-            self.decimals + self.decimal_offset()
+            self.decimals.saturating_add(self.decimal_offset())
         }
 
         /// Returns the decimal offset that this asset represents
+        #[ink(message)]
         pub fn decimal_offset(&self) -> u8 {
-            0
+            self.decimal_offset
         }
 
         /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
@@ -268,22 +1235,361 @@ mod erc4626 {
             self.allowances.get((owner, spender)).unwrap_or_default()
         }
 
-        #[inline]
-        fn real_deposit(
-            &mut self,
-            caller: AccountId,
-            receiver: AccountId,
-            assets: Balance,
-            shares: Balance,
-        ) -> Result<()> {
-            // @dev Must implement the transfer of vaulted asset to this address (vault)
-
+        /// The maximum amount of underlying assets that can be deposited by `receiver`
+        /// while the vault holds `total_assets`.
+        fn max_deposit_impl(&self, receiver: AccountId, total_assets: Balance) -> Balance {
+            // @dev You can change this function to change the maximum amount that
+            // can be deposited at a time
+            if self.deposits_paused {
+                return 0;
+            }
+            let owned_assets = self
+                .convert_to_assets_rounded(
+                    self.balance_of_impl(&receiver),
+                    Rounding::Ceil,
+                    total_assets,
+                )
+                .unwrap_or(Balance::MAX);
+            cap_headroom(self.deposit_cap, total_assets)
+                .min(cap_headroom(self.per_account_cap, owned_assets))
+        }
+
+        /// The shares minted for depositing `assets` while the vault holds
+        /// `total_assets`.
+        fn preview_deposit_impl(&self, assets: Balance, total_assets: Balance) -> Result<Balance> {
+            // @dev You can change this function to change the calculation of depositing
+            let fee = fee_on_total(assets, self.entry_fee_bps)?;
+            self.convert_to_shares_rounded(assets - fee, Rounding::Floor, total_assets)
+        }
+
+        /// The maximum amount of shares that can be minted to `receiver` while the
+        /// vault holds `total_assets`.
+        fn max_mint_impl(&self, receiver: AccountId, total_assets: Balance) -> Balance {
+            // @dev You can change this function to change the maximum amount of shares
+            // that can be minted at a time
+            // The entry fee is paid on top of the assets of the shares, so it must fit
+            // under the cap too
+            match self.max_deposit_impl(receiver, total_assets) {
+                Balance::MAX => Balance::MAX,
+                assets => self
+                    .preview_deposit_impl(assets, total_assets)
+                    .unwrap_or(Balance::MAX),
+            }
+        }
+
+        /// The assets taken for minting `shares` while the vault holds `total_assets`.
+        fn preview_mint_impl(&self, shares: Balance, total_assets: Balance) -> Result<Balance> {
+            // @dev You can change this function to change the calculation of minting
+            let assets = self.convert_to_assets_rounded(shares, Rounding::Ceil, total_assets)?;
+            assets
+                .checked_add(fee_on_raw(assets, self.entry_fee_bps)?)
+                .ok_or(Error::ArithmeticOverflow)
+        }
+
+        /// The maximum amount of underlying assets that can be withdrawn from `owner`
+        /// while the vault holds `total_assets`.
+        fn max_withdraw_impl(&self, owner: AccountId, total_assets: Balance) -> Balance {
+            // @dev You can change this function to change the maximum amount of assets
+            // that can be withdrawn at a time
+            if self.withdrawals_paused {
+                return 0;
+            }
+            self.preview_redeem_impl(self.balance_of_impl(&owner), total_assets)
+                .unwrap_or(0)
+        }
+
+        /// The shares burned for withdrawing `assets` while the vault holds
+        /// `total_assets`.
+        fn preview_withdraw_impl(&self, assets: Balance, total_assets: Balance) -> Result<Balance> {
+            // @dev You can change this function to change the calculation of withdrawing
+            let assets = assets
+                .checked_add(fee_on_raw(assets, self.exit_fee_bps)?)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.convert_to_shares_rounded(assets, Rounding::Ceil, total_assets)
+        }
+
+        /// The assets sent for redeeming `shares` while the vault holds `total_assets`.
+        fn preview_redeem_impl(&self, shares: Balance, total_assets: Balance) -> Result<Balance> {
+            // @dev You can change this function to change the calculation of redeeming
+            let assets = self.convert_to_assets_rounded(shares, Rounding::Floor, total_assets)?;
+            Ok(assets - fee_on_total(assets, self.exit_fee_bps)?)
+        }
+
+        /// Converts `assets` to shares while the vault holds `total_assets`, rounding in
+        /// the given direction.
+        fn convert_to_shares_rounded(
+            &self,
+            assets: Balance,
+            rounding: Rounding,
+            total_assets: Balance,
+        ) -> Result<Balance> {
+            let (supply, total_assets) = self.pricing_totals(total_assets)?;
+            mul_div(assets, supply, total_assets, rounding)
+        }
+
+        /// Converts `shares` to assets while the vault holds `total_assets`, rounding in
+        /// the given direction.
+        fn convert_to_assets_rounded(
+            &self,
+            shares: Balance,
+            rounding: Rounding,
+            total_assets: Balance,
+        ) -> Result<Balance> {
+            let (supply, total_assets) = self.pricing_totals(total_assets)?;
+            mul_div(shares, total_assets, supply, rounding)
+        }
+
+        /// Returns the total supply and total assets that shares are priced against
+        /// while the vault holds `total_assets`.
+        ///
+        /// Both include the virtual shares and assets added by the decimal offset, and
+        /// the supply includes the fee shares not yet minted.
+        fn pricing_totals(&self, total_assets: Balance) -> Result<(Balance, Balance)> {
+            let (supply, virtual_assets) = self.virtual_totals(total_assets)?;
+            let supply = supply
+                .checked_add(self.pending_fees(total_assets)?.shares)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok((supply, virtual_assets))
+        }
+
+        /// Returns the total supply and `total_assets` with the virtual shares and assets
+        /// added by the decimal offset.
+        fn virtual_totals(&self, total_assets: Balance) -> Result<(Balance, Balance)> {
+            let supply = 10_u128
+                .checked_pow(self.decimal_offset().into())
+                .and_then(|virtual_shares| self.total_supply.checked_add(virtual_shares))
+                .ok_or(Error::ArithmeticOverflow)?;
+            let total_assets = total_assets
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok((supply, total_assets))
+        }
+
+        /// Returns the fees accrued since the last accrual while the vault holds
+        /// `assets`.
+        ///
+        /// The management fee is charged on the total assets for the time elapsed, and
+        /// the performance fee on the gains of the share price above the high-water mark.
+        /// Both are paid by minting shares, diluting the other holders.
+        fn pending_fees(&self, assets: Balance) -> Result<PendingFees> {
+            let (supply, total_assets) = self.virtual_totals(assets)?;
+            if self.total_supply == 0 {
+                return Ok(PendingFees {
+                    management_fee: 0,
+                    performance_fee: 0,
+                    shares: 0,
+                    unpaid_fees: 0,
+                    high_water_mark: share_price(total_assets, supply),
+                });
+            }
+
+            let elapsed = self
+                .env()
+                .block_timestamp()
+                .saturating_sub(self.last_fee_accrual);
+            let management_fee = mul_div(
+                assets,
+                Balance::from(self.management_fee_bps) * Balance::from(elapsed),
+                Balance::from(MAX_BPS) * Balance::from(YEAR_MILLIS),
+                Rounding::Floor,
+            )?;
+            // The gain is compared in assets rather than in share prices, which would not
+            // fit a `Balance` once a share is worth far more than an asset. A mark that
+            // no longer fits is above any price a gain could be measured against
+            let mark_assets = if self.high_water_mark == Balance::MAX {
+                Balance::MAX
+            } else {
+                mul_div(
+                    self.high_water_mark,
+                    supply,
+                    SHARE_PRICE_PRECISION,
+                    Rounding::Ceil,
+                )
+                .unwrap_or(Balance::MAX)
+            };
+            let performance_fee = if self.performance_fee_bps > 0 && total_assets > mark_assets {
+                let gain = mul_div(
+                    total_assets - mark_assets,
+                    self.total_supply,
+                    supply,
+                    Rounding::Floor,
+                )?;
+                mul_div(
+                    gain,
+                    self.performance_fee_bps.into(),
+                    MAX_BPS.into(),
+                    Rounding::Floor,
+                )?
+            } else {
+                0
+            };
+
+            // Shares are minted at the price left once the fees are paid out of the assets.
+            // What is not worth a whole share stays owed in assets, so it is neither
+            // dropped nor charged again for time that was already paid for
+            let fees = self
+                .unpaid_fees
+                .saturating_add(management_fee)
+                .saturating_add(performance_fee)
+                .min(assets);
+            let shares = if fees == 0 {
+                0
+            } else {
+                mul_div(fees, supply, total_assets - fees, Rounding::Floor)?
+            };
+            let paid_fees = mul_div(shares, total_assets - fees, supply, Rounding::Ceil)?;
+            let unpaid_fees = fees.saturating_sub(paid_fees);
+            let supply = supply
+                .checked_add(shares)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok(PendingFees {
+                management_fee,
+                performance_fee,
+                shares,
+                unpaid_fees,
+                high_water_mark: self.high_water_mark.max(share_price(total_assets, supply)),
+            })
+        }
+
+        fn ensure_role(&self, role: RoleType) -> Result<()> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+
+        fn remove_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            if role == DEFAULT_ADMIN {
+                return Err(Error::InvalidRole);
+            }
+            if self.has_role(role, account) {
+                self.roles.remove((role, account));
+                self.env().emit_event(RoleRevoked {
+                    role,
+                    account,
+                    sender: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+
+        fn set_paused(&mut self, target: PauseTarget, paused: bool) -> Result<()> {
+            self.ensure_role(PAUSER)?;
+            if self.is_paused(target) == paused {
+                return Ok(());
+            }
+            match target {
+                PauseTarget::Deposits => self.deposits_paused = paused,
+                PauseTarget::Withdrawals => self.withdrawals_paused = paused,
+                PauseTarget::Transfers => self.transfers_paused = paused,
+            }
+            let account = self.env().caller();
+            if paused {
+                self.env().emit_event(Paused { target, account });
+            } else {
+                self.env().emit_event(Unpaused { target, account });
+            }
+            Ok(())
+        }
+
+        fn ensure_not_paused(&self, target: PauseTarget) -> Result<()> {
+            if self.is_paused(target) {
+                return Err(Error::Paused);
+            }
+            Ok(())
+        }
+
+        /// Returns the part of `fee` that leaves the vault, which is none of it when the
+        /// vault is its own fee recipient.
+        fn fee_to_send(&self, fee: Balance) -> Balance {
+            if self.fee_recipient == self.env().account_id() {
+                return 0;
+            }
+            fee
+        }
+
+        fn emit_fees_updated(&self) {
+            self.env().emit_event(FeesUpdated {
+                fee_recipient: self.fee_recipient,
+                management_fee_bps: self.management_fee_bps,
+                performance_fee_bps: self.performance_fee_bps,
+                entry_fee_bps: self.entry_fee_bps,
+                exit_fee_bps: self.exit_fee_bps,
+            });
+        }
+
+        fn emit_deposit_caps_updated(&self) {
+            self.env().emit_event(DepositCapsUpdated {
+                deposit_cap: self.deposit_cap,
+                per_account_cap: self.per_account_cap,
+            });
+        }
+
+        /// Runs `f` with the reentrancy lock held, failing if it is already held.
+        fn non_reentrant<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            if self.locked.get().unwrap_or(false) {
+                return Err(Error::Reentrancy);
+            }
+            self.locked.set(&true);
+            let result = f(self);
+            self.locked.set(&false);
+            result
+        }
+
+        /// Takes `assets` from `caller` and mints `shares` to `receiver`, where
+        /// `total_assets` is what the vault held before the deposit.
+        #[inline]
+        fn real_deposit(
+            &mut self,
+            caller: AccountId,
+            receiver: AccountId,
+            assets: Balance,
+            shares: Balance,
+            total_assets: Balance,
+        ) -> Result<()> {
+            self.non_reentrant(|vault| {
+                vault.do_deposit(caller, receiver, assets, shares, total_assets)
+            })
+        }
+
+        fn do_deposit(
+            &mut self,
+            caller: AccountId,
+            receiver: AccountId,
+            assets: Balance,
+            shares: Balance,
+            total_assets: Balance,
+        ) -> Result<()> {
+            self.do_accrue_fees(total_assets)?;
+
+            // Transfer of vaulted asset to this address (vault)
+            self.asset.pull(caller, assets)?;
+
             // Mint
-            let cur = self.balances.get(receiver).unwrap_or(0);
-            self.balances.insert(receiver, &(cur + shares));
+            let balance = self
+                .balance_of_impl(&receiver)
+                .checked_add(shares)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let total_supply = self
+                .total_supply
+                .checked_add(shares)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.balances.insert(receiver, &balance);
+            self.total_supply = total_supply;
+
+            // The entry fee is taken out of the deposited assets
+            let fee = self.fee_to_send(fee_on_total(assets, self.entry_fee_bps)?);
+            if fee > 0 {
+                self.asset.push(self.fee_recipient, fee)?;
+            }
 
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(receiver),
+                value: shares,
+            });
             self.env().emit_event(Deposit {
-                sender: self.env().caller(),
+                sender: caller,
                 owner: receiver,
                 assets,
                 shares,
@@ -291,6 +1597,13 @@ mod erc4626 {
             Ok(())
         }
 
+        /// Burns `shares` of `owner` and sends `assets` to `receiver`, where
+        /// `total_assets` is what the vault held before the withdrawal.
+        ///
+        /// Every check runs before the first storage write, so a failed check leaves the
+        /// vault untouched. A failed transfer of the underlying asset returns
+        /// `AssetTransferFailed` and undoes the burn, and ink! reverts the whole call on
+        /// top: balances, `total_supply` and allowances stay exactly as they were.
         #[inline]
         fn real_withdraw(
             &mut self,
@@ -299,26 +1612,72 @@ mod erc4626 {
             owner: AccountId,
             assets: Balance,
             shares: Balance,
+            total_assets: Balance,
+        ) -> Result<()> {
+            self.non_reentrant(|vault| {
+                vault.do_withdraw(caller, receiver, owner, assets, shares, total_assets)
+            })
+        }
+
+        fn do_withdraw(
+            &mut self,
+            caller: AccountId,
+            receiver: AccountId,
+            owner: AccountId,
+            assets: Balance,
+            shares: Balance,
+            total_assets: Balance,
         ) -> Result<()> {
+            self.do_accrue_fees(total_assets)?;
+
             // Spend allowance if necessary
-            if caller != owner {
-                let allowance = self.allowance_impl(&owner, &caller);
-                if allowance < shares {
-                    return Err(Error::InsufficientAllowance);
-                }
-                self.allowances
-                    .insert((&owner, &caller), &(allowance - shares));
-            }
+            let allowance = if caller != owner {
+                self.spent_allowance(&owner, &caller, shares)?
+            } else {
+                None
+            };
 
             // Burn
-            let cur = self.balances.get(receiver).unwrap_or(0);
-            if cur < shares {
-                return Err(Error::InsufficientBalance);
+            let balance = self
+                .balance_of_impl(&owner)
+                .checked_sub(shares)
+                .ok_or(Error::InsufficientBalance)?;
+            let total_supply = self
+                .total_supply
+                .checked_sub(shares)
+                .ok_or(Error::ArithmeticOverflow)?;
+            // The exit fee is taken on top of the withdrawn assets
+            let fee = self.fee_to_send(fee_on_raw(assets, self.exit_fee_bps)?);
+            if let Some(allowance) = allowance {
+                self.allowances.insert((&owner, &caller), &allowance);
+            }
+            self.balances.insert(owner, &balance);
+            self.total_supply = total_supply;
+
+            // Transfer of vaulted asset to the receiver
+            let sent = self.asset.push(receiver, assets).and_then(|()| {
+                if fee > 0 {
+                    self.asset.push(self.fee_recipient, fee)
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(error) = sent {
+                // Undoes the burn for callers that do not revert on errors
+                if let Some(allowance) = allowance {
+                    self.allowances
+                        .insert((&owner, &caller), &(allowance + shares));
+                }
+                self.balances.insert(owner, &(balance + shares));
+                self.total_supply = total_supply + shares;
+                return Err(error);
             }
-            self.balances.insert(receiver, &(cur - shares));
-
-            // @dev Must implement the transfer of valuted asset to the receiver
 
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: None,
+                value: shares,
+            });
             self.env().emit_event(Withdraw {
                 sender: caller,
                 receiver,
@@ -332,149 +1691,509 @@ mod erc4626 {
         // endregion
 
         /// Deposits assets of underlying tokens into the vault and grants ownership of shares to receiver.
+        ///
+        /// Returns the amount of shares minted.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self, assets: Balance, receiver: AccountId) -> Result<Balance> {
+            self.deposit_with_min_shares(assets, receiver, 0)
+        }
+
+        /// Mints exactly shares to receiver by depositing assets of underlying tokens.
+        ///
+        /// Returns the amount of assets deposited.
+        #[ink(message, payable)]
+        pub fn mint(&mut self, shares: Balance, receiver: AccountId) -> Result<Balance> {
+            self.mint_with_max_assets(shares, receiver, Balance::MAX)
+        }
+
+        /// Burns shares from owner and send exactly assets token from the vault to receiver.
+        ///
+        /// Returns the amount of shares burned.
+        #[ink(message)]
+        pub fn withdraw(
+            &mut self,
+            assets: Balance,
+            receiver: AccountId,
+            owner: AccountId,
+        ) -> Result<Balance> {
+            self.withdraw_with_max_shares(assets, receiver, owner, Balance::MAX)
+        }
+
+        /// Burns exactly shares from owner and sends assets token from the vault to receiver.
+        ///
+        /// Returns the amount of assets sent.
         #[ink(message)]
-        pub fn deposit(&mut self, assets: Balance, receiver: AccountId) -> Result<()> {
-            if assets > self.max_deposit(self.env().caller()) {
+        pub fn redeem(
+            &mut self,
+            shares: Balance,
+            receiver: AccountId,
+            owner: AccountId,
+        ) -> Result<Balance> {
+            self.redeem_with_min_assets(shares, receiver, owner, 0)
+        }
+
+        /// Deposits like `deposit`, but fails if fewer than `min_shares` shares would be
+        /// minted to the receiver.
+        #[ink(message, payable)]
+        pub fn deposit_with_min_shares(
+            &mut self,
+            assets: Balance,
+            receiver: AccountId,
+            min_shares: Balance,
+        ) -> Result<Balance> {
+            self.ensure_not_paused(PauseTarget::Deposits)?;
+            let total_assets = self.total_assets()?;
+            if assets > self.max_deposit_impl(receiver, total_assets) {
                 return Err(Error::ExceededMaxDeposit);
             }
 
-            let shares = self.preview_deposit(assets);
-            self.real_deposit(self.env().caller(), receiver, assets, shares)?;
-            Ok(())
+            let shares = self.preview_deposit_impl(assets, total_assets)?;
+            if shares < min_shares {
+                return Err(Error::InsufficientSharesOut);
+            }
+            self.real_deposit(self.env().caller(), receiver, assets, shares, total_assets)?;
+            Ok(shares)
         }
 
-        #[ink(message)]
-        pub fn mint(&mut self, shares: Balance, receiver: AccountId) -> Result<()> {
-            if shares > self.max_mint(receiver) {
+        /// Mints like `mint`, but fails if more than `max_assets` assets would be taken
+        /// from the caller.
+        #[ink(message, payable)]
+        pub fn mint_with_max_assets(
+            &mut self,
+            shares: Balance,
+            receiver: AccountId,
+            max_assets: Balance,
+        ) -> Result<Balance> {
+            self.ensure_not_paused(PauseTarget::Deposits)?;
+            let total_assets = self.total_assets()?;
+            if shares > self.max_mint_impl(receiver, total_assets) {
                 return Err(Error::ExceededMaxMint);
             }
 
-            let assets = self.preview_mint(shares);
-            self.real_deposit(self.env().caller(), receiver, assets, shares)?;
-            Ok(())
+            let assets = self.preview_mint_impl(shares, total_assets)?;
+            if assets > max_assets {
+                return Err(Error::ExceededMaxAssetsIn);
+            }
+            self.real_deposit(self.env().caller(), receiver, assets, shares, total_assets)?;
+            Ok(assets)
         }
 
-        /// Burns shares from owner and send exactly assets token from the vault to receiver.
+        /// Withdraws like `withdraw`, but fails if more than `max_shares` shares would be
+        /// burned from the owner.
         #[ink(message)]
-        pub fn withdraw(
+        pub fn withdraw_with_max_shares(
             &mut self,
             assets: Balance,
             receiver: AccountId,
             owner: AccountId,
-        ) -> Result<()> {
-            if assets > self.max_deposit(owner) {
+            max_shares: Balance,
+        ) -> Result<Balance> {
+            self.ensure_not_paused(PauseTarget::Withdrawals)?;
+            let total_assets = self.total_assets()?;
+            if assets > self.max_withdraw_impl(owner, total_assets) {
                 return Err(Error::ExceededMaxWithdraw);
             }
 
-            let shares = self.preview_withdraw(assets);
-            self.real_withdraw(self.env().caller(), receiver, owner, assets, shares)?;
-            Ok(())
+            let shares = self.preview_withdraw_impl(assets, total_assets)?;
+            if shares > max_shares {
+                return Err(Error::ExceededMaxSharesIn);
+            }
+            self.real_withdraw(
+                self.env().caller(),
+                receiver,
+                owner,
+                assets,
+                shares,
+                total_assets,
+            )?;
+            Ok(shares)
         }
 
-        /// Burns shares from owner and send exactly assets token from the vault to receiver.
+        /// Redeems like `redeem`, but fails if fewer than `min_assets` assets would be
+        /// sent to the receiver.
         #[ink(message)]
-        pub fn redeem(
+        pub fn redeem_with_min_assets(
             &mut self,
             shares: Balance,
             receiver: AccountId,
             owner: AccountId,
-        ) -> Result<()> {
+            min_assets: Balance,
+        ) -> Result<Balance> {
+            self.ensure_not_paused(PauseTarget::Withdrawals)?;
             if shares > self.max_redeem(owner) {
-                return Err(Error::ExceededMaxWithdraw);
+                return Err(Error::ExceededMaxRedeem);
             }
 
-            let assets = self.preview_redeem(shares);
-            self.real_withdraw(self.env().caller(), receiver, owner, assets, shares)?;
-            Ok(())
+            let total_assets = self.total_assets()?;
+            let assets = self.preview_redeem_impl(shares, total_assets)?;
+            if assets < min_assets {
+                return Err(Error::InsufficientAssetsOut);
+            }
+            self.real_withdraw(
+                self.env().caller(),
+                receiver,
+                owner,
+                assets,
+                shares,
+                total_assets,
+            )?;
+            Ok(assets)
         }
 
-        /// Transfers `value` amount of tokens from the caller's account to account `to`.
-        ///
-        /// On success a `Transfer` event is emitted.
+        /// Grants `role` to `account`. Only callable by the `DEFAULT_ADMIN`.
         ///
-        /// # Errors
+        /// A `RoleGranted` event is emitted if `account` did not hold `role` yet.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN)?;
+            if role == DEFAULT_ADMIN {
+                return Err(Error::InvalidRole);
+            }
+            if !self.has_role(role, account) {
+                self.roles.insert((role, account), &());
+                self.env().emit_event(RoleGranted {
+                    role,
+                    account,
+                    sender: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`. Only callable by the `DEFAULT_ADMIN`.
         ///
-        /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the caller's account balance.
+        /// A `RoleRevoked` event is emitted if `account` held `role`.
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
-            let from = self.env().caller();
-            self.transfer_from_to(&from, &to, value)
+        pub fn revoke_role(&mut self, role: RoleType, account: AccountId) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN)?;
+            self.remove_role(role, account)
         }
 
-        /// Allows `spender` to withdraw from the caller's account multiple times, up to
-        /// the `value` amount.
+        /// Revokes `role` from the caller.
         ///
-        /// If this function is called again it overwrites the current allowance with
-        /// `value`.
+        /// A `RoleRevoked` event is emitted if the caller held `role`.
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: RoleType) -> Result<()> {
+            self.remove_role(role, self.env().caller())
+        }
+
+        /// Starts transferring the `DEFAULT_ADMIN` role to `new_admin`, which takes
+        /// effect once `new_admin` calls `accept_admin`. Only callable by the
+        /// `DEFAULT_ADMIN`.
         ///
-        /// An `Approval` event is emitted.
+        /// Calling this again replaces the pending admin.
         #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
-            let owner = self.env().caller();
-            self.allowances.insert((&owner, &spender), &value);
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value,
+        pub fn transfer_admin(&mut self, new_admin: AccountId) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN)?;
+            self.pending_admin = Some(new_admin);
+            self.env().emit_event(AdminTransferStarted {
+                admin: self.admin,
+                pending_admin: new_admin,
             });
             Ok(())
         }
 
-        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        /// Accepts the `DEFAULT_ADMIN` role. Only callable by the pending admin.
+        #[ink(message)]
+        pub fn accept_admin(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_admin != Some(caller) {
+                return Err(Error::Unauthorized);
+            }
+            let previous_admin = self.admin;
+            self.admin = caller;
+            self.pending_admin = None;
+            self.env().emit_event(RoleRevoked {
+                role: DEFAULT_ADMIN,
+                account: previous_admin,
+                sender: caller,
+            });
+            self.env().emit_event(RoleGranted {
+                role: DEFAULT_ADMIN,
+                account: caller,
+                sender: caller,
+            });
+            Ok(())
+        }
+
+        /// Pauses `target`. Only callable by a `PAUSER`.
         ///
-        /// This can be used to allow a contract to transfer tokens on ones behalf and/or
-        /// to charge fees in sub-currencies, for example.
+        /// A `Paused` event is emitted if `target` was not paused yet.
+        #[ink(message)]
+        pub fn pause(&mut self, target: PauseTarget) -> Result<()> {
+            self.set_paused(target, true)
+        }
+
+        /// Unpauses `target`. Only callable by a `PAUSER`.
         ///
-        /// On success a `Transfer` event is emitted.
+        /// An `Unpaused` event is emitted if `target` was paused.
+        #[ink(message)]
+        pub fn unpause(&mut self, target: PauseTarget) -> Result<()> {
+            self.set_paused(target, false)
+        }
+
+        /// Mints the fees accrued since the last accrual as shares to the fee recipient.
         ///
-        /// # Errors
+        /// Fees are also accrued before every deposit and withdrawal, and are already
+        /// included in the conversions and previews. Returns the amount of shares minted.
         ///
-        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
-        /// for the caller to withdraw from `from`.
+        /// Fees worth less than a share are kept, in assets, and paid at a later accrual.
         ///
-        /// Returns `InsufficientBalance` error if there are not enough tokens on
-        /// the account balance of `from`.
+        /// A `FeesAccrued` event is emitted if any shares are minted.
         #[ink(message)]
-        pub fn transfer_from(
+        pub fn accrue_fees(&mut self) -> Result<Balance> {
+            self.non_reentrant(|vault| {
+                let total_assets = vault.total_assets()?;
+                vault.do_accrue_fees(total_assets)
+            })
+        }
+
+        /// Mints the fees accrued while the vault holds `total_assets` without taking the
+        /// reentrancy lock, for callers that already hold it or run before any asset is
+        /// moved.
+        fn do_accrue_fees(&mut self, total_assets: Balance) -> Result<Balance> {
+            let fees = self.pending_fees(total_assets)?;
+            if fees.shares > 0 {
+                let recipient = self.fee_recipient;
+                let balance = self
+                    .balance_of_impl(&recipient)
+                    .checked_add(fees.shares)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                let total_supply = self
+                    .total_supply
+                    .checked_add(fees.shares)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                self.balances.insert(recipient, &balance);
+                self.total_supply = total_supply;
+                self.env().emit_event(Transfer {
+                    from: None,
+                    to: Some(recipient),
+                    value: fees.shares,
+                });
+                self.env().emit_event(FeesAccrued {
+                    recipient,
+                    management_fee: fees.management_fee,
+                    performance_fee: fees.performance_fee,
+                    shares: fees.shares,
+                });
+            }
+            self.unpaid_fees = fees.unpaid_fees;
+            self.high_water_mark = fees.high_water_mark;
+            self.last_fee_accrual = self.env().block_timestamp();
+            Ok(fees.shares)
+        }
+
+        /// Sets the yearly management fee and the performance fee, in basis points.
+        /// Only callable by a `FEE_MANAGER`.
+        ///
+        /// Fees accrued so far are minted at the previous rates first.
+        #[ink(message)]
+        pub fn set_fees(
             &mut self,
-            from: AccountId,
-            to: AccountId,
-            value: Balance,
+            management_fee_bps: u16,
+            performance_fee_bps: u16,
         ) -> Result<()> {
-            let caller = self.env().caller();
-            let allowance = self.allowance_impl(&from, &caller);
-            if allowance < value {
-                return Err(Error::InsufficientAllowance);
+            self.ensure_role(FEE_MANAGER)?;
+            if management_fee_bps > MAX_BPS || performance_fee_bps > MAX_BPS {
+                return Err(Error::InvalidFee);
             }
-            self.transfer_from_to(&from, &to, value)?;
-            self.allowances
-                .insert((&from, &caller), &(allowance - value));
+            self.do_accrue_fees(self.total_assets()?)?;
+            self.management_fee_bps = management_fee_bps;
+            self.performance_fee_bps = performance_fee_bps;
+            self.emit_fees_updated();
             Ok(())
         }
 
-        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        /// Sets the entry and exit fees, in basis points. Only callable by a
+        /// `FEE_MANAGER`.
         ///
-        /// On success a `Transfer` event is emitted.
+        /// Both fees are sent to the fee recipient in the underlying asset.
+        #[ink(message)]
+        pub fn set_entry_exit_fees(&mut self, entry_fee_bps: u16, exit_fee_bps: u16) -> Result<()> {
+            self.ensure_role(FEE_MANAGER)?;
+            if entry_fee_bps > MAX_BPS || exit_fee_bps > MAX_BPS {
+                return Err(Error::InvalidFee);
+            }
+            self.entry_fee_bps = entry_fee_bps;
+            self.exit_fee_bps = exit_fee_bps;
+            self.emit_fees_updated();
+            Ok(())
+        }
+
+        /// Sets the account that receives the fee shares and the entry and exit fees.
+        /// Only callable by a `FEE_MANAGER`.
+        ///
+        /// Fees accrued so far are minted to the previous recipient first.
+        #[ink(message)]
+        pub fn set_fee_recipient(&mut self, fee_recipient: AccountId) -> Result<()> {
+            self.ensure_role(FEE_MANAGER)?;
+            self.do_accrue_fees(self.total_assets()?)?;
+            self.fee_recipient = fee_recipient;
+            self.emit_fees_updated();
+            Ok(())
+        }
+
+        /// Sets the total assets up to which the vault accepts deposits.
+        ///
+        /// `Balance::MAX` removes the cap. Only callable by the `DEFAULT_ADMIN`.
+        #[ink(message)]
+        pub fn set_deposit_cap(&mut self, deposit_cap: Balance) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN)?;
+            self.deposit_cap = deposit_cap;
+            self.emit_deposit_caps_updated();
+            Ok(())
+        }
+
+        /// Sets the assets up to which a single account may hold in the vault.
+        ///
+        /// `Balance::MAX` removes the cap. Only callable by the `DEFAULT_ADMIN`.
+        #[ink(message)]
+        pub fn set_per_account_cap(&mut self, per_account_cap: Balance) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN)?;
+            self.per_account_cap = per_account_cap;
+            self.emit_deposit_caps_updated();
+            Ok(())
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
         ///
         /// # Errors
         ///
         /// Returns `InsufficientBalance` error if there are not enough tokens on
         /// the caller's account balance.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            let from = self.env().caller();
+            self.transfer_from_to(&from, &to, value)
+        }
+
+        /// Allows `spender` to withdraw from the caller's account multiple times, up to
+        /// the `value` amount.
+        ///
+        /// If this function is called again it overwrites the current allowance with
+        /// `value`. An allowance of `Balance::MAX` is infinite and never spent.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.approve_impl(owner, spender, value);
+            Ok(())
+        }
+
+        /// Increases the allowance of `spender` over the caller's account by
+        /// `delta_value`, without the race of overwriting it with `approve`.
+        ///
+        /// An `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `ArithmeticOverflow` error if the allowance would exceed
+        /// `Balance::MAX`.
+        #[ink(message)]
+        pub fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            let allowance = self
+                .allowance_impl(&owner, &spender)
+                .checked_add(delta_value)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.approve_impl(owner, spender, allowance);
+            Ok(())
+        }
+
+        /// Decreases the allowance of `spender` over the caller's account by
+        /// `delta_value`, without the race of overwriting it with `approve`.
+        ///
+        /// An `Approval` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `AllowanceBelowZero` error if the allowance is lower than
+        /// `delta_value`.
+        #[ink(message)]
+        pub fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<()> {
+            let owner = self.env().caller();
+            let allowance = self
+                .allowance_impl(&owner, &spender)
+                .checked_sub(delta_value)
+                .ok_or(Error::AllowanceBelowZero)?;
+            self.approve_impl(owner, spender, allowance);
+            Ok(())
+        }
+
+        /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+        ///
+        /// This can be used to allow a contract to transfer tokens on ones behalf and/or
+        /// to charge fees in sub-currencies, for example.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientAllowance` error if there are not enough tokens allowed
+        /// for the caller to withdraw from `from`.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the account balance of `from`.
+        #[ink(message)]
+        pub fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<()> {
+            self.non_reentrant(|vault| {
+                let caller = vault.env().caller();
+                let allowance = vault.spent_allowance(&from, &caller, value)?;
+                vault.transfer_from_to(&from, &to, value)?;
+                if let Some(allowance) = allowance {
+                    vault.allowances.insert((&from, &caller), &allowance);
+                }
+                Ok(())
+            })
+        }
+
+        /// Transfers `value` amount of tokens from the caller's account to account `to`.
+        ///
+        /// On success a `Transfer` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        ///
+        /// Returns `ArithmeticOverflow` error if the balance of `to` would overflow.
         fn transfer_from_to(
             &mut self,
             from: &AccountId,
             to: &AccountId,
             value: Balance,
         ) -> Result<()> {
+            self.ensure_not_paused(PauseTarget::Transfers)?;
             let from_balance = self.balance_of_impl(from);
             if from_balance < value {
                 return Err(Error::InsufficientBalance);
             }
 
-            self.balances.insert(from, &(from_balance - value));
-            let to_balance = self.balance_of_impl(to);
-            self.balances.insert(to, &(to_balance + value));
+            // A transfer to oneself leaves the balance unchanged and cannot overflow.
+            if from != to {
+                let to_balance = self
+                    .balance_of_impl(to)
+                    .checked_add(value)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                self.balances.insert(from, &(from_balance - value));
+                self.balances.insert(to, &to_balance);
+            }
             self.env().emit_event(Transfer {
                 from: Some(*from),
                 to: Some(*to),
@@ -482,14 +2201,134 @@ mod erc4626 {
             });
             Ok(())
         }
+
+        /// Returns the allowance of `spender` over the shares of `owner` left after
+        /// spending `value`, or `None` if the allowance is infinite and stays unchanged.
+        fn spent_allowance(
+            &self,
+            owner: &AccountId,
+            spender: &AccountId,
+            value: Balance,
+        ) -> Result<Option<Balance>> {
+            let allowance = self.allowance_impl(owner, spender);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance);
+            }
+            Ok((allowance != Balance::MAX).then(|| allowance - value))
+        }
+
+        /// Sets the allowance of `spender` over the shares of `owner` to `value`.
+        ///
+        /// An `Approval` event is emitted.
+        fn approve_impl(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.allowances.insert((&owner, &spender), &value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+    }
+
+    /// The vault's shares as a PSP22 token. The `data` of transfers is ignored.
+    impl PSP22 for Erc4626 {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balance_of_impl(&owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowance_impl(&owner, &spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            Erc4626::transfer(self, to, value).map_err(Into::into)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            Erc4626::transfer_from(self, from, to, value).map_err(Into::into)
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            Erc4626::approve(self, spender, value).map_err(Into::into)
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            Erc4626::increase_allowance(self, spender, delta_value).map_err(Into::into)
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            Erc4626::decrease_allowance(self, spender, delta_value).map_err(Into::into)
+        }
     }
 
-    #[cfg(test)]
+    impl PSP22Metadata for Erc4626 {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            Some(self.name())
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            Some(self.symbol())
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals()
+        }
+    }
+
+    #[cfg(all(test, not(any(feature = "psp22", feature = "pallet-assets"))))]
     mod tests {
         use super::*;
 
         use ink::primitives::{Clear, Hash};
 
+        #[cfg(not(feature = "zeitgeist"))]
+        use super::asset::NativeAsset;
+
+        /// The native currency of the chain under the enabled backend.
+        #[cfg(not(feature = "zeitgeist"))]
+        const NATIVE_ASSET: AssetId = NativeAsset;
+        /// The native currency of the chain under the enabled backend.
+        #[cfg(feature = "zeitgeist")]
+        const NATIVE_ASSET: AssetId = crate::ZeitgeistAsset::Ztg;
+
         type Event = <Erc4626 as ::ink::reflect::ContractEventBase>::Type;
 
         fn assert_transfer_event(
@@ -541,95 +2380,937 @@ mod erc4626 {
             }
         }
 
-        /// The default constructor does its job.
-        #[ink::test]
-        fn new_works() {
-            // Constructor works.
-            let _erc20 = Erc4626::new(100, 10);
+        /// The default constructor does its job.
+        #[ink::test]
+        fn new_works() {
+            // Constructor works.
+            let erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+
+            // The vault starts without any shares.
+            assert_eq!(erc20.total_supply(), 0);
+            assert_eq!(ink::env::test::recorded_events().count(), 0);
+        }
+
+        #[ink::test]
+        fn new_with_seed_works() {
+            set_contract_balance(1000);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
+            let erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 1000).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+
+            // The seed deposit backs the shares held by the dead account.
+            let dead = AccountId::from(DEAD_ACCOUNT);
+            assert_eq!(erc20.balance_of(dead), erc20.total_supply());
+            assert_eq!(erc20.total_supply(), 1000);
+            assert_eq!(erc20.total_assets(), Ok(1000));
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(dead), 1000);
+        }
+
+        /// The total supply was applied.
+        #[ink::test]
+        fn total_supply_works() {
+            // Constructor works.
+            let erc20 = vault_with_shares(100);
+            // Get the token total supply.
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        /// The decimals was applied.
+        #[ink::test]
+        fn decimals_works() {
+            // Constructor works.
+            let erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+            // Get the decimals.
+            assert_eq!(erc20.decimals(), 10);
+        }
+
+        /// The name and symbol are derived from the asset.
+        #[cfg(not(feature = "zeitgeist"))]
+        #[ink::test]
+        fn name_and_symbol_work() {
+            let erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+            assert_eq!(erc20.name(), "Vault UNIT");
+            assert_eq!(erc20.symbol(), "vUNIT");
+            assert_eq!(erc20.token_symbol(), Some(String::from("vUNIT")));
+        }
+
+        /// Get the actual balance of an account.
+        #[ink::test]
+        fn balance_of_works() {
+            // Constructor works
+            let erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            // Alice owns all the tokens on contract instantiation
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            // Bob does not owns tokens
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn asset_works() {
+            let erc20 = vault_with_shares(100);
+            assert_eq!(erc20.asset(), NATIVE_ASSET);
+        }
+
+        #[ink::test]
+        fn total_assets_excludes_existential_deposit_and_transferred_value() {
+            let erc20 = vault_with_shares(100);
+            set_contract_balance(0);
+            assert_eq!(erc20.total_assets(), Ok(0));
+
+            set_contract_balance(300);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(erc20.total_assets(), Ok(200));
+        }
+
+        #[ink::test]
+        fn convert_to_shares_works() {
+            let erc20 = vault_with_shares(100);
+            set_contract_balance(200);
+            assert_eq!(erc20.convert_to_shares(100), Ok(50));
+        }
+
+        #[ink::test]
+        fn convert_to_assets_works() {
+            let erc20 = vault_with_shares(100);
+            set_contract_balance(200);
+            assert_eq!(erc20.convert_to_assets(100), Ok(199));
+        }
+
+        #[ink::test]
+        fn share_price_follows_total_assets() {
+            let erc20 = vault_with_shares(100);
+            set_contract_balance(200);
+            let assets_before = erc20.convert_to_assets(100).unwrap();
+
+            // The vault doubles its holdings, so each share is worth twice as much.
+            set_contract_balance(401);
+            assert_eq!(erc20.convert_to_assets(100), Ok(398));
+            assert!(erc20.convert_to_assets(100).unwrap() >= assets_before * 2);
+        }
+
+        #[ink::test]
+        fn previews_round_in_favour_of_the_vault() {
+            let erc20 = vault_with_shares(100);
+            set_contract_balance(200);
+
+            // Depositing and redeeming round down the amount received by the user.
+            assert_eq!(erc20.preview_deposit(100), Ok(50));
+            assert_eq!(erc20.preview_redeem(100), Ok(199));
+            // Withdrawing and minting round up the amount owed by the user.
+            assert_eq!(erc20.preview_withdraw(100), Ok(51));
+            assert_eq!(erc20.preview_mint(100), Ok(200));
+        }
+
+        #[ink::test]
+        fn mul_div_uses_full_precision() {
+            // The intermediate product exceeds `u128::MAX`, but the result fits.
+            assert_eq!(
+                mul_div(u128::MAX, u128::MAX, u128::MAX, Rounding::Floor),
+                Ok(u128::MAX)
+            );
+            assert_eq!(mul_div(7, 3, 2, Rounding::Floor), Ok(10));
+            assert_eq!(mul_div(7, 3, 2, Rounding::Ceil), Ok(11));
+            assert_eq!(mul_div(6, 3, 2, Rounding::Ceil), Ok(9));
+        }
+
+        #[ink::test]
+        fn mul_div_overflow_fails() {
+            assert_eq!(
+                mul_div(u128::MAX, 2, 1, Rounding::Floor),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(
+                mul_div(1, 1, 0, Rounding::Floor),
+                Err(Error::ArithmeticOverflow)
+            );
+        }
+
+        #[ink::test]
+        fn conversion_overflow_fails() {
+            let mut erc20 = vault_with_shares(100);
+            set_contract_balance(1);
+            assert_eq!(
+                erc20.convert_to_shares(u128::MAX),
+                Err(Error::ArithmeticOverflow)
+            );
+
+            erc20.total_supply = u128::MAX;
+            assert_eq!(erc20.convert_to_assets(1), Err(Error::ArithmeticOverflow));
+        }
+
+        #[ink::test]
+        fn deposit_and_redeem_move_native_assets() {
+            let mut erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // The transferred value is credited to the vault before the message executes.
+            set_contract_balance(100);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(erc20.deposit(100, accounts.bob), Ok(100));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.total_assets(), Ok(100));
+            assert_eq!(erc20.redeem(40, accounts.bob, accounts.bob), Ok(40));
+            assert_eq!(erc20.total_assets(), Ok(60));
+            assert_eq!(erc20.total_supply(), 60);
+
+            // Shares are minted from and burned to no account.
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(&emitted_events[0], None, Some(accounts.bob), 100);
+            assert_transfer_event(&emitted_events[2], Some(accounts.bob), None, 40);
+        }
+
+        #[ink::test]
+        fn donation_attack_is_unprofitable() {
+            let mut erc20 = Erc4626::new(NATIVE_ASSET, 10, 3, 0).unwrap();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // The attacker deposits a single unit into the empty vault.
+            set_contract_balance(1);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+            assert_eq!(erc20.deposit(1, accounts.alice), Ok(1000));
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+
+            // The attacker then donates to the vault to inflate the share price.
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            set_contract_balance(10_001);
+
+            // The victim's deposit still mints shares worth nearly all of it.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            set_contract_balance(20_001);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10_000);
+            assert_eq!(erc20.deposit(10_000, accounts.bob), Ok(1999));
+            assert_eq!(erc20.balance_of(accounts.bob), 1999);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(erc20.preview_redeem(1999), Ok(9998));
+
+            // The attacker gets back far less than the 10_001 they put in.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.preview_redeem(1000), Ok(5001));
+            assert_eq!(erc20.redeem(1000, accounts.eve, accounts.alice), Ok(5001));
+            assert_eq!(erc20.total_assets(), Ok(15_000));
+        }
+
+        #[ink::test]
+        fn deposit_balance_overflow_fails() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(200);
+            erc20.balances.insert(accounts.bob, &u128::MAX);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+
+            assert_eq!(
+                erc20.deposit(100, accounts.bob),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), u128::MAX);
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn deposit_supply_overflow_fails() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(1_000_000);
+            erc20.total_supply = u128::MAX - 1;
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+
+            assert_eq!(
+                erc20.deposit(100, accounts.bob),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.total_supply(), u128::MAX - 1);
+        }
+
+        #[ink::test]
+        fn mint_balance_overflow_fails() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(200);
+            erc20.balances.insert(accounts.bob, &u128::MAX);
+            let assets = erc20.preview_mint(10).unwrap();
+            set_contract_balance(200 + assets);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(assets);
+
+            assert_eq!(erc20.mint(10, accounts.bob), Err(Error::ArithmeticOverflow));
+            assert_eq!(erc20.balance_of(accounts.bob), u128::MAX);
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn max_deposit_and_max_mint_are_unlimited() {
+            let erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.max_deposit(accounts.bob), u128::MAX);
+            assert_eq!(erc20.max_mint(accounts.bob), u128::MAX);
+        }
+
+        #[ink::test]
+        fn max_withdraw_is_owned_assets() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(200);
+
+            assert_eq!(erc20.max_withdraw(accounts.alice), 199);
+            assert_eq!(erc20.max_withdraw(accounts.bob), 0);
+            assert_eq!(
+                erc20.withdraw(200, accounts.eve, accounts.alice),
+                Err(Error::ExceededMaxWithdraw)
+            );
+            assert_eq!(erc20.withdraw(199, accounts.eve, accounts.alice), Ok(100));
+            assert_eq!(erc20.max_withdraw(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn max_redeem_is_share_balance() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(200);
+
+            assert_eq!(erc20.max_redeem(accounts.alice), 100);
+            assert_eq!(erc20.max_redeem(accounts.bob), 0);
+            assert_eq!(
+                erc20.redeem(101, accounts.eve, accounts.alice),
+                Err(Error::ExceededMaxRedeem)
+            );
+            assert_eq!(erc20.redeem(100, accounts.eve, accounts.alice), Ok(199));
+            assert_eq!(erc20.max_redeem(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn roles_can_be_granted_revoked_and_renounced() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(erc20.has_role(DEFAULT_ADMIN, accounts.alice));
+            assert!(!erc20.has_role(PAUSER, accounts.bob));
+
+            assert_eq!(erc20.grant_role(PAUSER, accounts.bob), Ok(()));
+            assert_eq!(erc20.grant_role(STRATEGIST, accounts.bob), Ok(()));
+            assert!(erc20.has_role(PAUSER, accounts.bob));
+            assert_eq!(erc20.revoke_role(PAUSER, accounts.bob), Ok(()));
+            assert!(!erc20.has_role(PAUSER, accounts.bob));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.renounce_role(STRATEGIST), Ok(()));
+            assert!(!erc20.has_role(STRATEGIST, accounts.bob));
+            assert_eq!(
+                erc20.grant_role(FEE_MANAGER, accounts.bob),
+                Err(Error::Unauthorized)
+            );
+            assert_eq!(ink::env::test::recorded_events().count(), 4);
+        }
+
+        #[ink::test]
+        fn default_admin_is_only_transferred_in_two_steps() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(
+                erc20.grant_role(DEFAULT_ADMIN, accounts.bob),
+                Err(Error::InvalidRole)
+            );
+            assert_eq!(erc20.renounce_role(DEFAULT_ADMIN), Err(Error::InvalidRole));
+
+            assert_eq!(erc20.transfer_admin(accounts.bob), Ok(()));
+            assert_eq!(erc20.pending_admin(), Some(accounts.bob));
+            assert_eq!(erc20.admin(), accounts.alice);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.accept_admin(), Err(Error::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.accept_admin(), Ok(()));
+            assert_eq!(erc20.admin(), accounts.bob);
+            assert_eq!(erc20.pending_admin(), None);
+            assert!(!erc20.has_role(DEFAULT_ADMIN, accounts.alice));
+        }
+
+        #[ink::test]
+        fn paused_deposits_still_allow_withdrawals() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(200);
+            assert_eq!(erc20.grant_role(PAUSER, accounts.alice), Ok(()));
+            assert_eq!(erc20.pause(PauseTarget::Deposits), Ok(()));
+            assert!(erc20.is_paused(PauseTarget::Deposits));
+
+            assert_eq!(erc20.max_deposit(accounts.bob), 0);
+            assert_eq!(erc20.max_mint(accounts.bob), 0);
+            assert_eq!(erc20.deposit(0, accounts.bob), Err(Error::Paused));
+            assert_eq!(erc20.mint(0, accounts.bob), Err(Error::Paused));
+            assert_eq!(erc20.redeem(50, accounts.eve, accounts.alice), Ok(99));
+        }
+
+        #[ink::test]
+        fn paused_withdrawals_still_allow_deposits() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.grant_role(PAUSER, accounts.alice), Ok(()));
+            assert_eq!(erc20.pause(PauseTarget::Withdrawals), Ok(()));
+
+            assert_eq!(erc20.max_withdraw(accounts.alice), 0);
+            assert_eq!(erc20.max_redeem(accounts.alice), 0);
+            assert_eq!(
+                erc20.withdraw(0, accounts.alice, accounts.alice),
+                Err(Error::Paused)
+            );
+            assert_eq!(
+                erc20.redeem(0, accounts.alice, accounts.alice),
+                Err(Error::Paused)
+            );
+            set_contract_balance(300);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(erc20.deposit(100, accounts.bob), Ok(50));
+
+            assert_eq!(erc20.unpause(PauseTarget::Withdrawals), Ok(()));
+            assert_eq!(erc20.max_redeem(accounts.alice), 100);
+        }
+
+        #[ink::test]
+        fn paused_transfers_fail() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(
+                erc20.pause(PauseTarget::Transfers),
+                Err(Error::Unauthorized)
+            );
+
+            assert_eq!(erc20.grant_role(PAUSER, accounts.alice), Ok(()));
+            assert_eq!(erc20.pause(PauseTarget::Transfers), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Err(Error::Paused));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            assert_eq!(erc20.unpause(PauseTarget::Transfers), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 10), Ok(()));
+            // `RoleGranted`, `Paused`, `Unpaused` and `Transfer`.
+            assert_eq!(ink::env::test::recorded_events().count(), 4);
+        }
+
+        #[ink::test]
+        fn management_fee_accrues_over_time() {
+            let mut erc20 = vault_with_shares(1000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(1000);
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fee_recipient(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_fees(200, 0), Ok(()));
+
+            // A year later 2% of the assets are owed as fees.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(YEAR_MILLIS);
+            let assets = erc20.convert_to_assets(1000).unwrap();
+            assert_eq!(erc20.accrue_fees(), Ok(20));
+            assert_eq!(erc20.balance_of(accounts.charlie), 20);
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let mint = &emitted_events[emitted_events.len() - 2];
+            assert_transfer_event(mint, None, Some(accounts.charlie), 20);
+            // Conversions already included the pending fees.
+            assert_eq!(erc20.convert_to_assets(1000), Ok(assets));
+            assert_eq!(erc20.accrue_fees(), Ok(0));
+        }
+
+        #[ink::test]
+        fn performance_fee_is_charged_above_high_water_mark() {
+            let mut erc20 = vault_with_shares(1000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(1000);
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fee_recipient(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_fees(0, 1000), Ok(()));
+            assert_eq!(erc20.accrue_fees(), Ok(0));
+
+            // The vault gains 1000 assets, 10% of which are owed as fees.
+            set_contract_balance(2000);
+            assert_eq!(erc20.accrue_fees(), Ok(52));
+            assert_eq!(erc20.balance_of(accounts.charlie), 52);
+            assert_eq!(erc20.accrue_fees(), Ok(0));
+
+            // Recovering from a loss up to the high-water mark is not charged.
+            set_contract_balance(1500);
+            assert_eq!(erc20.accrue_fees(), Ok(0));
+            set_contract_balance(2000);
+            assert_eq!(erc20.accrue_fees(), Ok(0));
+            assert_eq!(erc20.balance_of(accounts.charlie), 52);
+        }
+
+        #[ink::test]
+        fn fees_below_one_share_are_carried_forward() {
+            let mut erc20 = vault_with_shares(1000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(10_000);
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fee_recipient(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_fees(200, 1000), Ok(()));
+
+            // The management fee is not worth a share yet, so it is kept in assets.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(YEAR_MILLIS / 100);
+            assert_eq!(erc20.accrue_fees(), Ok(0));
+            assert_eq!(erc20.unpaid_fees(), 2);
+            assert_eq!(erc20.last_fee_accrual, YEAR_MILLIS / 100);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(YEAR_MILLIS);
+            set_contract_balance(20_000);
+            let fees = erc20.pending_fees(erc20.total_assets().unwrap()).unwrap();
+            assert_eq!(fees.management_fee, 396);
+            assert_eq!(fees.performance_fee, 999);
+            assert_eq!(fees.shares, 75);
+            assert_eq!(erc20.accrue_fees(), Ok(75));
+            assert_eq!(erc20.balance_of(accounts.charlie), 75);
+            // The 2 unpaid assets were paid too, and what 75 shares do not cover is kept.
+            assert_eq!(erc20.unpaid_fees(), 2 + 396 + 999 - 1394);
+        }
+
+        #[ink::test]
+        fn deposit_after_carried_fees_keeps_its_value() {
+            let mut erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(10);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(erc20.deposit(10, accounts.alice), Ok(10));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fee_recipient(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_fees(200, 0), Ok(()));
+
+            // Four years of fees on 10 assets never reach a share.
+            for year in 1..=4 {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                    year * YEAR_MILLIS,
+                );
+                assert_eq!(erc20.accrue_fees(), Ok(0));
+            }
+            assert_eq!(erc20.unpaid_fees(), 0);
+
+            // The depositor does not pay for the years before the deposit.
+            let deposit = 1_000_000_000_000;
+            set_contract_balance(10 + deposit);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(deposit);
+            let shares = erc20.deposit(deposit, accounts.bob).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert!(erc20.preview_redeem(shares).unwrap() >= deposit - 1);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn expensive_shares_do_not_overflow_the_share_price() {
+            let mut erc20 = Erc4626::new(NATIVE_ASSET, 18, 0, 0).unwrap();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let token = 10_u128.pow(18);
+
+            // A donation to the empty vault prices a share far above `SHARE_PRICE_PRECISION`.
+            set_contract_balance(400 * token);
+            assert_eq!(erc20.convert_to_shares(1000 * token), Ok(2));
+            assert_eq!(erc20.max_deposit(accounts.bob), u128::MAX);
+            set_contract_balance(1400 * token);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000 * token);
+            assert_eq!(erc20.deposit(1000 * token, accounts.bob), Ok(2));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fees(200, 1000), Ok(()));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(YEAR_MILLIS);
+            set_contract_balance(2000 * token);
+            assert!(erc20.convert_to_assets(2).is_ok());
+            assert!(erc20.accrue_fees().is_ok());
+        }
+
+        #[ink::test]
+        fn deposit_accrues_fees_first() {
+            let mut erc20 = vault_with_shares(1000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(1000);
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fee_recipient(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_fees(200, 0), Ok(()));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(YEAR_MILLIS);
+
+            let shares = erc20.preview_deposit(100).unwrap();
+            set_contract_balance(1100);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(erc20.deposit(100, accounts.bob), Ok(shares));
+            assert_eq!(erc20.balance_of(accounts.charlie), 20);
+        }
+
+        #[ink::test]
+        fn only_fee_manager_sets_valid_fees() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.set_fees(100, 100), Err(Error::Unauthorized));
+            assert_eq!(
+                erc20.set_fee_recipient(accounts.bob),
+                Err(Error::Unauthorized)
+            );
+
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fees(MAX_BPS + 1, 0), Err(Error::InvalidFee));
+            assert_eq!(erc20.set_fees(100, 2000), Ok(()));
+            assert_eq!(erc20.management_fee(), 100);
+            assert_eq!(erc20.performance_fee(), 2000);
+            assert_eq!(erc20.fee_recipient(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn previews_include_entry_and_exit_fees() {
+            let mut erc20 = vault_with_shares(1000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(1000);
+            let shares = erc20.preview_deposit(1000).unwrap();
+            let assets = erc20.preview_redeem(shares).unwrap();
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_entry_exit_fees(500, 1000), Ok(()));
+
+            // 5% is added on top of the assets deposited for `shares`, rounding up.
+            assert_eq!(erc20.preview_deposit(1050), Ok(shares));
+            assert_eq!(erc20.preview_deposit(1049), Ok(shares - 1));
+            assert_eq!(erc20.preview_mint(shares), Ok(1050));
+            // 10% is added on top of the assets withdrawn, rounding up.
+            assert_eq!(erc20.preview_withdraw(assets), Ok(1100));
+            assert_eq!(erc20.preview_redeem(1100), Ok(assets));
+            assert_eq!(erc20.preview_redeem(1099), Ok(assets - 1));
+            assert_eq!(erc20.preview_redeem(1), Ok(0));
+        }
+
+        #[ink::test]
+        fn entry_and_exit_fees_are_sent_to_fee_recipient() {
+            let mut erc20 = vault_with_shares(1000);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_fee_recipient(accounts.charlie), Ok(()));
+            assert_eq!(erc20.set_entry_exit_fees(500, 1000), Ok(()));
+            let fees_before = account_balance(accounts.charlie);
+
+            set_contract_balance(2050);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1050);
+            assert_eq!(erc20.deposit(1050, accounts.bob), Ok(1000));
+            assert_eq!(account_balance(accounts.charlie), fees_before + 50);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(erc20.total_assets(), Ok(2000));
+            let received_before = account_balance(accounts.eve);
+            assert_eq!(erc20.redeem(500, accounts.eve, accounts.alice), Ok(454));
+            assert_eq!(account_balance(accounts.eve), received_before + 454);
+            assert_eq!(account_balance(accounts.charlie), fees_before + 96);
+            assert_eq!(erc20.total_assets(), Ok(1500));
+        }
+
+        #[ink::test]
+        fn deposit_caps_limit_deposits() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(100);
+
+            assert_eq!(erc20.set_deposit_cap(250), Ok(()));
+            assert_eq!(erc20.max_deposit(accounts.bob), 150);
+            assert_eq!(erc20.set_per_account_cap(120), Ok(()));
+            assert_eq!(erc20.max_deposit(accounts.bob), 120);
+            // Alice already holds assets in the vault.
+            assert_eq!(erc20.max_deposit(accounts.alice), 20);
+            assert_eq!(erc20.max_mint(accounts.alice), 20);
+
+            assert_eq!(
+                erc20.deposit(121, accounts.bob),
+                Err(Error::ExceededMaxDeposit)
+            );
+            assert_eq!(erc20.mint(21, accounts.alice), Err(Error::ExceededMaxMint));
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn max_mint_pays_the_entry_fee_under_the_cap() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(100);
+            assert_eq!(erc20.grant_role(FEE_MANAGER, accounts.alice), Ok(()));
+            assert_eq!(erc20.set_entry_exit_fees(1000, 0), Ok(()));
+            assert_eq!(erc20.set_deposit_cap(250), Ok(()));
+
+            let max_deposit = erc20.max_deposit(accounts.bob);
+            let max_mint = erc20.max_mint(accounts.bob);
+            assert_eq!(max_deposit, 150);
+            assert_eq!(max_mint, 136);
+            assert!(erc20.preview_mint(max_mint).unwrap() <= max_deposit);
+            assert!(erc20.preview_mint(max_mint + 1).unwrap() > max_deposit);
+        }
+
+        #[ink::test]
+        fn only_admin_sets_deposit_caps() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            assert_eq!(erc20.set_deposit_cap(0), Err(Error::Unauthorized));
+            assert_eq!(erc20.set_per_account_cap(0), Err(Error::Unauthorized));
+            assert_eq!(erc20.deposit_cap(), u128::MAX);
+            assert_eq!(erc20.per_account_cap(), u128::MAX);
+        }
+
+        #[ink::test]
+        fn deposit_and_mint_respect_slippage_bounds() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(300);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+
+            assert_eq!(
+                erc20.deposit_with_min_shares(100, accounts.bob, 51),
+                Err(Error::InsufficientSharesOut)
+            );
+            let assets = erc20.preview_mint(50).unwrap();
+            assert_eq!(
+                erc20.mint_with_max_assets(50, accounts.bob, assets - 1),
+                Err(Error::ExceededMaxAssetsIn)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            assert_eq!(erc20.deposit_with_min_shares(100, accounts.bob, 50), Ok(50));
+            assert_eq!(erc20.balance_of(accounts.bob), 50);
+        }
+
+        #[ink::test]
+        fn withdraw_and_redeem_respect_slippage_bounds() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(200);
+
+            assert_eq!(
+                erc20.withdraw_with_max_shares(100, accounts.eve, accounts.alice, 50),
+                Err(Error::ExceededMaxSharesIn)
+            );
+            assert_eq!(
+                erc20.redeem_with_min_assets(100, accounts.eve, accounts.alice, 200),
+                Err(Error::InsufficientAssetsOut)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+
+            assert_eq!(
+                erc20.redeem_with_min_assets(100, accounts.eve, accounts.alice, 199),
+                Ok(199)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn guarded_messages_fail_while_locked() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.alice, 10), Ok(()));
+
+            // A call back into the vault sees the lock held by the outer call.
+            erc20.locked.set(&true);
+            assert_eq!(erc20.deposit(0, accounts.bob), Err(Error::Reentrancy));
+            assert_eq!(erc20.mint(0, accounts.bob), Err(Error::Reentrancy));
+            assert_eq!(
+                erc20.withdraw(0, accounts.bob, accounts.alice),
+                Err(Error::Reentrancy)
+            );
+            assert_eq!(
+                erc20.redeem(0, accounts.bob, accounts.alice),
+                Err(Error::Reentrancy)
+            );
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 10),
+                Err(Error::Reentrancy)
+            );
+            assert_eq!(erc20.accrue_fees(), Err(Error::Reentrancy));
+
+            // The lock is released once the outer call returns, even on failure.
+            erc20.locked.set(&false);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 11),
+                Err(Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 10),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn increase_and_decrease_allowance_work() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.increase_allowance(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 4), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 6);
+            assert_eq!(
+                erc20.decrease_allowance(accounts.bob, 7),
+                Err(Error::AllowanceBelowZero)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 6);
+
+            // Overflowing fails instead of making the allowance infinite.
+            assert_eq!(
+                erc20.increase_allowance(accounts.bob, Balance::MAX),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 6);
+            assert_eq!(
+                erc20.increase_allowance(accounts.bob, Balance::MAX - 6),
+                Ok(())
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), Balance::MAX);
+
+            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 3);
+            for event in emitted_events {
+                let decoded = <Event as scale::Decode>::decode(&mut &event.data[..])
+                    .expect("encountered invalid contract event data buffer");
+                assert!(matches!(decoded, Event::Approval(_)));
+            }
+        }
+
+        #[ink::test]
+        fn infinite_allowance_is_not_spent() {
+            let mut erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(100);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(erc20.deposit(100, accounts.alice), Ok(100));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(erc20.approve(accounts.bob, Balance::MAX), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.eve, 10),
+                Ok(())
+            );
+            assert_eq!(erc20.redeem(10, accounts.eve, accounts.alice), Ok(10));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), Balance::MAX);
+            assert_eq!(erc20.balance_of(accounts.alice), 80);
+
+            // A finite allowance is still spent.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.approve(accounts.bob, 20), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.redeem(10, accounts.eve, accounts.alice), Ok(10));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn psp22_messages_work() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                PSP22::transfer(&mut erc20, accounts.bob, 10, vec![1, 2]),
+                Ok(())
+            );
+            assert_eq!(PSP22::balance_of(&erc20, accounts.bob), 10);
+            assert_eq!(PSP22::total_supply(&erc20), 100);
+
+            assert_eq!(PSP22::approve(&mut erc20, accounts.bob, 10), Ok(()));
+            assert_eq!(
+                PSP22::increase_allowance(&mut erc20, accounts.bob, 5),
+                Ok(())
+            );
+            assert_eq!(
+                PSP22::decrease_allowance(&mut erc20, accounts.bob, 3),
+                Ok(())
+            );
+            assert_eq!(PSP22::allowance(&erc20, accounts.alice, accounts.bob), 12);
+            assert_eq!(
+                PSP22::decrease_allowance(&mut erc20, accounts.bob, 13),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                ink::env::test::recorded_events().count(),
+                4,
+                "one Transfer and three Approval events"
+            );
 
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(1, emitted_events.len());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                PSP22::transfer_from(&mut erc20, accounts.alice, accounts.eve, 12, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(PSP22::balance_of(&erc20, accounts.eve), 12);
+            assert_eq!(
+                PSP22::transfer_from(&mut erc20, accounts.alice, accounts.eve, 1, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
 
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+            // Vault errors without a PSP22 counterpart are passed on by name.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            erc20.transfers_paused = true;
+            assert_eq!(
+                PSP22::transfer(&mut erc20, accounts.bob, 1, Vec::new()),
+                Err(PSP22Error::Custom(String::from("Paused")))
             );
+            assert_eq!(erc20.token_decimals(), erc20.decimals());
+            assert_eq!(erc20.token_name(), Some(erc20.name()));
         }
 
-        /// The total supply was applied.
         #[ink::test]
-        fn total_supply_works() {
-            // Constructor works.
-            let erc20 = Erc4626::new(100, 10);
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+        fn failed_withdraw_leaves_state_unchanged() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 200), Ok(()));
+            let total_assets = erc20.total_assets().unwrap();
+
+            // Fails on the owner's balance, after the allowance was checked.
+            assert_eq!(
+                erc20.real_withdraw(
+                    accounts.bob,
+                    accounts.eve,
+                    accounts.alice,
+                    150,
+                    150,
+                    total_assets
+                ),
+                Err(Error::InsufficientBalance)
             );
-            // Get the token total supply.
+
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 200);
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
             assert_eq!(erc20.total_supply(), 100);
         }
 
-        /// The decimals was applied.
         #[ink::test]
-        fn decimals_works() {
-            // Constructor works.
-            let erc20 = Erc4626::new(100, 10);
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
-            // Get the decimals.
-            assert_eq!(erc20.decimals(), 10);
-        }
+        fn failed_native_transfer_undoes_the_burn() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_contract_balance(50);
+            assert_eq!(erc20.approve(accounts.bob, 200), Ok(()));
+            let eve_balance = account_balance(accounts.eve);
 
-        /// Get the actual balance of an account.
-        #[ink::test]
-        fn balance_of_works() {
-            // Constructor works
-            let erc20 = Erc4626::new(100, 10);
-            // Transfer event triggered during initial construction
-            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
+            // Every check passes, but the vault cannot pay out more than it holds.
+            assert_eq!(
+                erc20.real_withdraw(accounts.bob, accounts.eve, accounts.alice, 80, 80, 50),
+                Err(Error::AssetTransferFailed)
             );
-            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            // Alice owns all the tokens on contract instantiation
+
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 200);
             assert_eq!(erc20.balance_of(accounts.alice), 100);
-            // Bob does not owns tokens
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(erc20.total_assets(), Ok(50));
+            assert_eq!(account_balance(accounts.eve), eve_balance);
         }
 
         #[ink::test]
-        fn convert_to_shares_works() {
-            let erc20 = Erc4626::new(100, 10);
-            assert_eq!(erc20.convert_to_shares(100), 100);
-        }
+        fn transfer_overflow_fails() {
+            let mut erc20 = vault_with_shares(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            erc20.balances.insert(accounts.bob, &u128::MAX);
 
-        #[ink::test]
-        fn convert_to_assets_works() {
-            let erc20 = Erc4626::new(100, 10);
-            assert_eq!(erc20.convert_to_assets(100), 100);
+            assert_eq!(
+                erc20.transfer(accounts.bob, 1),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.balance_of(accounts.bob), u128::MAX);
         }
 
         #[ink::test]
         fn transfer_works() {
             // Constructor works.
-            let mut erc20 = Erc4626::new(100, 10);
-            // Transfer event triggered during initial construction.
+            let mut erc20 = vault_with_shares(100);
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
             assert_eq!(erc20.balance_of(accounts.bob), 0);
@@ -639,17 +3320,10 @@ mod erc4626 {
             assert_eq!(erc20.balance_of(accounts.bob), 10);
 
             let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 2);
-            // Check first transfer event related to ERC-20 instantiation.
+            assert_eq!(emitted_events.len(), 1);
+            // Check the transfer event relating to the actual trasfer.
             assert_transfer_event(
                 &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
-            // Check the second transfer event relating to the actual trasfer.
-            assert_transfer_event(
-                &emitted_events[1],
                 Some(AccountId::from([0x01; 32])),
                 Some(AccountId::from([0x02; 32])),
                 10,
@@ -659,7 +3333,7 @@ mod erc4626 {
         #[ink::test]
         fn invalid_transfer_should_fail() {
             // Constructor works.
-            let mut erc20 = Erc4626::new(100, 10);
+            let mut erc20 = vault_with_shares(100);
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
             assert_eq!(erc20.balance_of(accounts.bob), 0);
@@ -679,22 +3353,14 @@ mod erc4626 {
             assert_eq!(erc20.balance_of(accounts.bob), 0);
             assert_eq!(erc20.balance_of(accounts.eve), 0);
 
-            // Transfer event triggered during initial construction.
-            let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 1);
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
+            // No events were emitted.
+            assert_eq!(ink::env::test::recorded_events().count(), 0);
         }
 
         #[ink::test]
         fn transfer_from_works() {
             // Constructor works.
-            let mut erc20 = Erc4626::new(100, 10);
-            // Transfer event triggered during initial construction.
+            let mut erc20 = vault_with_shares(100);
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
             // Bob fails to transfer tokens owned by Alice.
@@ -706,7 +3372,7 @@ mod erc4626 {
             assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
 
             // The approve event takes place.
-            assert_eq!(ink::env::test::recorded_events().count(), 2);
+            assert_eq!(ink::env::test::recorded_events().count(), 1);
 
             // Set the contract as callee and Bob as caller.
             let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
@@ -723,17 +3389,11 @@ mod erc4626 {
 
             // Check all transfer events that happened during the previous calls:
             let emitted_events = ink::env::test::recorded_events().collect::<Vec<_>>();
-            assert_eq!(emitted_events.len(), 3);
-            assert_transfer_event(
-                &emitted_events[0],
-                None,
-                Some(AccountId::from([0x01; 32])),
-                100,
-            );
-            // The second event `emitted_events[1]` is an Approve event that we skip
+            assert_eq!(emitted_events.len(), 2);
+            // The first event `emitted_events[0]` is an Approve event that we skip
             // checking.
             assert_transfer_event(
-                &emitted_events[2],
+                &emitted_events[1],
                 Some(AccountId::from([0x01; 32])),
                 Some(AccountId::from([0x05; 32])),
                 10,
@@ -742,7 +3402,7 @@ mod erc4626 {
 
         #[ink::test]
         fn allowance_must_not_change_on_failed_transfer() {
-            let mut erc20 = Erc4626::new(100, 10);
+            let mut erc20 = vault_with_shares(100);
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
 
             // Alice approves Bob for token transfers on her behalf.
@@ -773,6 +3433,32 @@ mod erc4626 {
             )
         }
 
+        /// Creates a vault in which Alice holds `shares` that are not backed by assets.
+        fn vault_with_shares(shares: Balance) -> Erc4626 {
+            let mut erc20 = Erc4626::new(NATIVE_ASSET, 10, 0, 0).unwrap();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            erc20.balances.insert(accounts.alice, &shares);
+            erc20.total_supply = shares;
+            erc20
+        }
+
+        /// Returns the native balance of `account`.
+        fn account_balance(account: AccountId) -> Balance {
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account)
+                .unwrap_or(0)
+        }
+
+        /// Sets the native balance held by the contract under test on top of its
+        /// existential deposit.
+        fn set_contract_balance(balance: Balance) {
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            let minimum_balance = ink::env::minimum_balance::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract,
+                balance + minimum_balance,
+            );
+        }
+
         /// For calculating the event topic hash.
         struct PrefixedValue<'a, 'b, T> {
             pub prefix: &'a [u8],
@@ -820,130 +3506,512 @@ mod erc4626 {
         }
     }
 
-    #[cfg(all(test, feature = "e2e-tests"))]
+    #[cfg(all(test, feature = "psp22"))]
+    mod psp22_tests {
+        use super::asset::{Psp22Asset, VaultAsset};
+        use super::*;
+
+        #[ink::test]
+        fn psp22_asset_is_identified_by_its_contract() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let asset = Psp22Asset::from_id(accounts.django).unwrap();
+            assert_eq!(asset.id(), accounts.django);
+        }
+
+        #[ink::test]
+        fn psp22_asset_rejects_native_value() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let asset = Psp22Asset::from_id(accounts.django).unwrap();
+
+            // Native value sent along with a deposit would be stuck in the vault.
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+            assert_eq!(
+                asset.pull(accounts.alice, 100),
+                Err(Error::AssetTransferFailed)
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "pallet-assets"))]
+    mod pallet_assets_tests {
+        use super::asset::{PalletAsset, VaultAsset, ASSETS_BALANCE_OF_FUNC_ID};
+        use super::*;
+
+        const ASSET: PalletAsset = PalletAsset {
+            pallet_index: 50,
+            asset_id: 7,
+        };
+
+        /// A mock of the chain extension returning the `pallet-assets` balance of the
+        /// vault.
+        struct MockAssets {
+            vault_balance: Balance,
+            status_code: u32,
+        }
+
+        impl ink::env::test::ChainExtension for MockAssets {
+            fn func_id(&self) -> u32 {
+                ASSETS_BALANCE_OF_FUNC_ID
+            }
+
+            fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+                let input = <Vec<u8> as scale::Decode>::decode(&mut &input[..]).unwrap();
+                let (asset_id, _) =
+                    <(u32, AccountId) as scale::Decode>::decode(&mut &input[..]).unwrap();
+                let balance = if asset_id == ASSET.asset_id {
+                    self.vault_balance
+                } else {
+                    0
+                };
+                scale::Encode::encode_to(&balance, output);
+                self.status_code
+            }
+        }
+
+        #[ink::test]
+        fn pallet_asset_vault_reads_its_balance() {
+            ink::env::test::register_chain_extension(MockAssets {
+                vault_balance: 501,
+                status_code: 0,
+            });
+            let erc20 = Erc4626::new(ASSET, 12, 1, 0).unwrap();
+            assert_eq!(erc20.asset(), ASSET);
+            assert_eq!(erc20.total_assets(), Ok(501));
+            // 501 assets and one virtual asset against ten virtual shares
+            assert_eq!(erc20.preview_deposit(502), Ok(10));
+            assert_eq!(erc20.decimals(), 13);
+            assert_eq!(erc20.name(), "Vault ASSET-7");
+            assert_eq!(erc20.symbol(), "vASSET-7");
+        }
+
+        #[ink::test]
+        fn pallet_asset_rejects_native_value() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::register_chain_extension(MockAssets {
+                vault_balance: 0,
+                status_code: 0,
+            });
+            let mut erc20 = Erc4626::new(ASSET, 12, 1, 0).unwrap();
+
+            // Native value sent along with a deposit would be stuck in the vault.
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+            assert_eq!(
+                UnderlyingAsset::from_id(ASSET)
+                    .unwrap()
+                    .pull(accounts.alice, 100),
+                Err(Error::AssetTransferFailed)
+            );
+            assert_eq!(
+                erc20.deposit(100, accounts.bob),
+                Err(Error::AssetTransferFailed)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.total_supply(), 0);
+        }
+
+        #[ink::test]
+        fn failed_balance_query_is_an_error() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::register_chain_extension(MockAssets {
+                vault_balance: 0,
+                status_code: 0,
+            });
+            let erc20 = Erc4626::new(ASSET, 12, 1, 0).unwrap();
+
+            // An error of the runtime is not taken for an empty vault.
+            ink::env::test::register_chain_extension(MockAssets {
+                vault_balance: 0,
+                status_code: 1,
+            });
+            assert_eq!(erc20.total_assets(), Err(Error::AssetQueryFailed));
+            assert_eq!(erc20.convert_to_shares(100), Err(Error::AssetQueryFailed));
+            assert_eq!(erc20.max_deposit(accounts.bob), 0);
+        }
+    }
+
+    #[cfg(all(test, feature = "zeitgeist"))]
+    mod zeitgeist_tests {
+        use super::asset::{CURRENCY_BALANCE_OF_FUNC_ID, CURRENCY_TRANSFER_FROM_CALLER_FUNC_ID};
+        use super::*;
+        use crate::{ScalarPosition, ZeitgeistAsset};
+        use std::{cell::Cell, rc::Rc};
+
+        /// A mock of a function of the `AssetManager` chain extension, sharing the
+        /// balance of the vault with the other functions.
+        struct MockCurrency {
+            func_id: u32,
+            vault_balance: Rc<Cell<Balance>>,
+        }
+
+        impl ink::env::test::ChainExtension for MockCurrency {
+            fn func_id(&self) -> u32 {
+                self.func_id
+            }
+
+            fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+                let input = <Vec<u8> as scale::Decode>::decode(&mut &input[..]).unwrap();
+                let balance = self.vault_balance.get();
+                match self.func_id {
+                    CURRENCY_BALANCE_OF_FUNC_ID => {
+                        scale::Encode::encode_to(&balance, output);
+                        0
+                    }
+                    _ => {
+                        let (_, amount) =
+                            <(ZeitgeistAsset, Balance) as scale::Decode>::decode(&mut &input[..])
+                                .unwrap();
+                        self.vault_balance.set(balance + amount);
+                        0
+                    }
+                }
+            }
+        }
+
+        /// Registers the `AssetManager` chain extension, holding `vault_balance` of the
+        /// vault's currency.
+        fn register_currency(vault_balance: Balance) -> Rc<Cell<Balance>> {
+            let vault_balance = Rc::new(Cell::new(vault_balance));
+            for func_id in [
+                CURRENCY_BALANCE_OF_FUNC_ID,
+                CURRENCY_TRANSFER_FROM_CALLER_FUNC_ID,
+            ] {
+                ink::env::test::register_chain_extension(MockCurrency {
+                    func_id,
+                    vault_balance: vault_balance.clone(),
+                });
+            }
+            vault_balance
+        }
+
+        #[ink::test]
+        fn ztg_vault_is_named_after_ztg() {
+            let erc20 = Erc4626::new(ZeitgeistAsset::Ztg, 10, 1, 0).unwrap();
+            assert_eq!(erc20.asset(), ZeitgeistAsset::Ztg);
+            assert_eq!(erc20.name(), "Vault ZTG");
+            assert_eq!(erc20.symbol(), "vZTG");
+        }
+
+        #[ink::test]
+        fn foreign_asset_vault_reads_its_balance() {
+            register_currency(500);
+            let erc20 = Erc4626::new(ZeitgeistAsset::ForeignAsset(1), 12, 1, 0).unwrap();
+            assert_eq!(erc20.asset(), ZeitgeistAsset::ForeignAsset(1));
+            assert_eq!(erc20.total_assets(), Ok(500));
+            assert_eq!(erc20.decimals(), 13);
+            assert_eq!(erc20.token_name(), Some(String::from("Vault FOREIGN-1")));
+            assert_eq!(erc20.token_symbol(), Some(String::from("vFOREIGN-1")));
+        }
+
+        #[ink::test]
+        fn currencies_are_named_explicitly() {
+            register_currency(0);
+            for (currency, symbol) in [
+                (ZeitgeistAsset::CategoricalOutcome(3, 1), "vCAT-3-1"),
+                (
+                    ZeitgeistAsset::ScalarOutcome(4, ScalarPosition::Long),
+                    "vSCALAR-4-LONG",
+                ),
+                (
+                    ZeitgeistAsset::ScalarOutcome(4, ScalarPosition::Short),
+                    "vSCALAR-4-SHORT",
+                ),
+                (ZeitgeistAsset::PoolShare(5), "vPOOL-5"),
+            ] {
+                let erc20 = Erc4626::new(currency, 10, 0, 0).unwrap();
+                assert_eq!(erc20.symbol(), symbol);
+            }
+        }
+
+        #[ink::test]
+        fn combinatorial_outcomes_are_rejected() {
+            assert!(matches!(
+                Erc4626::new(ZeitgeistAsset::CombinatorialOutcome, 10, 0, 0),
+                Err(Error::UnsupportedAsset)
+            ));
+        }
+
+        #[ink::test]
+        fn foreign_asset_deposit_works() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let vault_balance = register_currency(0);
+            let mut erc20 = Erc4626::new(ZeitgeistAsset::ForeignAsset(1), 12, 0, 0).unwrap();
+
+            assert_eq!(erc20.deposit(100, accounts.alice), Ok(100));
+            assert_eq!(vault_balance.get(), 100);
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.total_assets(), Ok(100));
+            assert_eq!(erc20.max_withdraw(accounts.alice), 100);
+        }
+
+        #[ink::test]
+        fn withdrawals_call_the_asset_manager() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let call = crate::RuntimeCall::AssetManager(crate::AssetManagerCall::Transfer {
+                dest: accounts.bob.into(),
+                currency_id: ZeitgeistAsset::ForeignAsset(1),
+                amount: 40,
+            });
+            let encoded = scale::Encode::encode(&call);
+            // The pallet index, the call index and the `MultiAddress::Id` variant
+            assert_eq!(encoded[..3], [40, 0, 0]);
+            assert_eq!(encoded[3..35], *AsRef::<[u8]>::as_ref(&accounts.bob));
+        }
+
+        #[ink::test]
+        fn foreign_asset_donations_do_not_mint_shares() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let vault_balance = register_currency(0);
+            let mut erc20 = Erc4626::new(ZeitgeistAsset::ForeignAsset(1), 12, 0, 0).unwrap();
+            assert_eq!(erc20.deposit(100, accounts.alice), Ok(100));
+
+            // A donation raises the share price instead of being credited to anyone
+            vault_balance.set(vault_balance.get() + 100);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.deposit(100, accounts.bob), Ok(50));
+            assert_eq!(vault_balance.get(), 300);
+        }
+
+        /// A mock of the deposit function of the `AssetManager` chain extension whose
+        /// transfers always fail.
+        struct FailingTransfer;
+
+        impl ink::env::test::ChainExtension for FailingTransfer {
+            fn func_id(&self) -> u32 {
+                CURRENCY_TRANSFER_FROM_CALLER_FUNC_ID
+            }
+
+            fn call(&mut self, _input: &[u8], _output: &mut Vec<u8>) -> u32 {
+                1
+            }
+        }
+
+        #[ink::test]
+        fn failed_transfer_leaves_the_vault_unchanged() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let vault_balance = register_currency(0);
+            let mut erc20 = Erc4626::new(ZeitgeistAsset::ForeignAsset(1), 12, 0, 0).unwrap();
+            assert_eq!(erc20.deposit(100, accounts.alice), Ok(100));
+
+            ink::env::test::register_chain_extension(FailingTransfer);
+            assert_eq!(
+                erc20.deposit(40, accounts.alice),
+                Err(Error::AssetTransferFailed)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 100);
+            assert_eq!(erc20.total_supply(), 100);
+            assert_eq!(erc20.total_assets(), Ok(100));
+            assert_eq!(vault_balance.get(), 100);
+        }
+
+        #[ink::test]
+        fn foreign_asset_rejects_native_value() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let vault_balance = register_currency(0);
+            let mut erc20 = Erc4626::new(ZeitgeistAsset::ForeignAsset(1), 12, 0, 0).unwrap();
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+            assert_eq!(
+                erc20.deposit(100, accounts.alice),
+                Err(Error::AssetTransferFailed)
+            );
+            assert_eq!(vault_balance.get(), 0);
+        }
+    }
+
+    #[cfg(all(
+        test,
+        feature = "e2e-tests",
+        not(any(feature = "psp22", feature = "pallet-assets", feature = "zeitgeist"))
+    ))]
     mod e2e_tests {
         use super::*;
         use ink_e2e::build_message;
         type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
         #[ink_e2e::test]
-        async fn e2e_transfer(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn e2e_deposit_and_redeem(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // given
-            let total_supply = 1_000_000_000;
-            let constructor = Erc20Ref::new(total_supply);
+            let constructor = Erc4626Ref::new(asset::NativeAsset, 12, 0, 0);
             let contract_acc_id = client
-                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .instantiate("erc_4626", &ink_e2e::alice(), constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
 
             // when
-            let total_supply_msg = build_message::<Erc20Ref>(contract_acc_id.clone())
-                .call(|erc20| erc20.total_supply());
-            let total_supply_res = client
-                .call_dry_run(&ink_e2e::bob(), &total_supply_msg, 0, None)
-                .await;
+            let deposit = build_message::<Erc4626Ref>(contract_acc_id)
+                .call(|erc20| erc20.deposit(1_000_000, alice_account));
+            let deposit_res = client
+                .call(&ink_e2e::alice(), deposit, 1_000_000, None)
+                .await
+                .expect("deposit failed");
 
-            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
-            let transfer_to_bob = 500_000_000u128;
-            let transfer = build_message::<Erc20Ref>(contract_acc_id.clone())
-                .call(|erc20| erc20.transfer(bob_account.clone(), transfer_to_bob));
-            let _transfer_res = client
-                .call(&ink_e2e::alice(), transfer, 0, None)
+            let bob_balance = client.balance(bob_account).await.expect("balance failed");
+            let redeem = build_message::<Erc4626Ref>(contract_acc_id)
+                .call(|erc20| erc20.redeem(400_000, bob_account, alice_account));
+            let redeem_res = client
+                .call(&ink_e2e::alice(), redeem, 0, None)
                 .await
-                .expect("transfer failed");
+                .expect("redeem failed");
 
-            let balance_of = build_message::<Erc20Ref>(contract_acc_id.clone())
-                .call(|erc20| erc20.balance_of(bob_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+            let total_assets =
+                build_message::<Erc4626Ref>(contract_acc_id).call(|erc20| erc20.total_assets());
+            let total_assets_res = client
+                .call_dry_run(&ink_e2e::alice(), &total_assets, 0, None)
                 .await;
 
             // then
+            assert_eq!(deposit_res.return_value(), Ok(1_000_000));
+            assert_eq!(redeem_res.return_value(), Ok(400_000));
             assert_eq!(
-                total_supply,
-                total_supply_res.return_value(),
-                "total_supply"
+                client.balance(bob_account).await.expect("balance failed"),
+                bob_balance + 400_000
             );
-            assert_eq!(transfer_to_bob, balance_of_res.return_value(), "balance_of");
+            assert_eq!(total_assets_res.return_value(), Ok(600_000));
 
             Ok(())
         }
 
         #[ink_e2e::test]
-        async fn e2e_allowances(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn e2e_deposit_needs_value(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // given
-            let total_supply = 1_000_000_000;
-            let constructor = Erc20Ref::new(total_supply);
+            let constructor = Erc4626Ref::new(asset::NativeAsset, 12, 0, 0);
             let contract_acc_id = client
-                .instantiate("erc20", &ink_e2e::bob(), constructor, 0, None)
+                .instantiate("erc_4626", &ink_e2e::bob(), constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
-
-            // when
-
             let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
-            let charlie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
 
-            let amount = 500_000_000u128;
-            let transfer_from = build_message::<Erc20Ref>(contract_acc_id.clone()).call(|erc20| {
-                erc20.transfer_from(bob_account.clone(), charlie_account.clone(), amount)
-            });
-            let transfer_from_result = client
-                .call(&ink_e2e::charlie(), transfer_from, 0, None)
+            // when
+            let deposit = build_message::<Erc4626Ref>(contract_acc_id)
+                .call(|erc20| erc20.deposit(1000, bob_account));
+            let deposit_res = client
+                .call_dry_run(&ink_e2e::bob(), &deposit, 999, None)
                 .await;
 
-            assert!(
-                transfer_from_result.is_err(),
-                "unapproved transfer_from should fail"
+            // then
+            assert_eq!(
+                deposit_res.return_value(),
+                Err(Error::InsufficientAllowance)
             );
 
-            // Bob approves Charlie to transfer up to amount on his behalf
-            let approved_value = 1_000u128;
-            let approve_call = build_message::<Erc20Ref>(contract_acc_id.clone())
-                .call(|erc20| erc20.approve(charlie_account.clone(), approved_value));
-            client
-                .call(&ink_e2e::bob(), approve_call, 0, None)
-                .await
-                .expect("approve failed");
+            Ok(())
+        }
+    }
+}
 
-            // `transfer_from` the approved amount
-            let transfer_from = build_message::<Erc20Ref>(contract_acc_id.clone()).call(|erc20| {
-                erc20.transfer_from(bob_account.clone(), charlie_account.clone(), approved_value)
-            });
-            let transfer_from_result = client
-                .call(&ink_e2e::charlie(), transfer_from, 0, None)
-                .await;
-            assert!(
-                transfer_from_result.is_ok(),
-                "approved transfer_from should succeed"
-            );
+/// A call to `pallet-assets`, dispatched by the vault through `call_runtime`.
+///
+/// The pallet index depends on the position of `pallet-assets` in the
+/// `construct_runtime!` block of the runtime, so it is configured per vault instead of
+/// through a `#[codec(index)]` attribute.
+///
+/// [See here for more.](https://substrate.stackexchange.com/questions/778/how-to-get-pallet-index-u8-of-a-pallet-in-runtime)
+#[cfg(feature = "pallet-assets")]
+pub struct RuntimeCall {
+    pub pallet_index: u8,
+    pub call: AssetsCall,
+}
 
-            let balance_of = build_message::<Erc20Ref>(contract_acc_id.clone())
-                .call(|erc20| erc20.balance_of(bob_account));
-            let balance_of_res = client
-                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
-                .await;
+#[cfg(feature = "pallet-assets")]
+impl scale::Encode for RuntimeCall {
+    fn size_hint(&self) -> usize {
+        1 + self.call.size_hint()
+    }
 
-            // `transfer_from` again, this time exceeding the approved amount
-            let transfer_from = build_message::<Erc20Ref>(contract_acc_id.clone())
-                .call(|erc20| erc20.transfer_from(bob_account.clone(), charlie_account.clone(), 1));
-            let transfer_from_result = client
-                .call(&ink_e2e::charlie(), transfer_from, 0, None)
-                .await;
-            assert!(
-                transfer_from_result.is_err(),
-                "transfer_from exceeding the approved amount should fail"
-            );
+    fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+        self.pallet_index.encode_to(dest);
+        self.call.encode_to(dest);
+    }
+}
 
-            assert_eq!(
-                total_supply - approved_value,
-                balance_of_res.return_value(),
-                "balance_of"
-            );
+#[cfg(feature = "pallet-assets")]
+#[derive(scale::Encode, scale::Decode)]
+pub enum AssetsCall {
+    // `pallet_assets::Call::transfer`
+    #[codec(index = 8)]
+    Transfer {
+        #[codec(compact)]
+        id: u32,
+        target: MultiAddress<AccountId, ()>,
+        #[codec(compact)]
+        amount: u128,
+    },
+    // `pallet_assets::Call::transfer_approved`, spending an approval given to the vault
+    #[codec(index = 25)]
+    TransferApproved {
+        #[codec(compact)]
+        id: u32,
+        owner: MultiAddress<AccountId, ()>,
+        destination: MultiAddress<AccountId, ()>,
+        #[codec(compact)]
+        amount: u128,
+    },
+}
 
-            Ok(())
-        }
-    }
+/// A call to the runtime of Zeitgeist, dispatched by the vault through `call_runtime`.
+#[cfg(feature = "zeitgeist")]
+#[derive(scale::Encode, scale::Decode)]
+pub enum RuntimeCall {
+    /// This index can be found by investigating runtime configuration. You can check the
+    /// pallet order inside `construct_runtime!` block and read the position of your
+    /// pallet (0-based).
+    ///
+    /// https://github.com/zeitgeistpm/zeitgeist/blob/3d9bbff91219bb324f047427224ee318061a6d43/runtime/common/src/lib.rs#L254-L363
+    ///
+    /// [See here for more.](https://substrate.stackexchange.com/questions/778/how-to-get-pallet-index-u8-of-a-pallet-in-runtime)
+    ///
+    /// @dev Replace with the index of `AssetManager` in your runtime.
+    #[codec(index = 40)]
+    AssetManager(AssetManagerCall),
+}
+
+#[cfg(feature = "zeitgeist")]
+#[derive(scale::Encode, scale::Decode)]
+pub enum AssetManagerCall {
+    // https://github.com/open-web3-stack/open-runtime-module-library/blob/22a4f7b7d1066c1a138222f4546d527d32aa4047/currencies/src/lib.rs#L129-L131C19
+    #[codec(index = 0)]
+    Transfer {
+        dest: MultiAddress<AccountId, ()>,
+        currency_id: ZeitgeistAsset,
+        #[codec(compact)]
+        amount: u128,
+    },
+}
+
+/// A currency of the `AssetManager` pallet of Zeitgeist.
+#[cfg(feature = "zeitgeist")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ZeitgeistAsset {
+    /// An outcome of a categorical market, by market ID and category index.
+    CategoricalOutcome(u128, u16),
+    /// An outcome of a scalar market, by market ID and position.
+    ScalarOutcome(u128, ScalarPosition),
+    /// Not tradable on the chain yet, so vaults over it cannot be created.
+    CombinatorialOutcome,
+    /// The share of a liquidity pool, by pool ID.
+    PoolShare(u128),
+    /// The native currency of Zeitgeist.
+    Ztg,
+    /// An asset bridged from another chain, by asset ID.
+    ForeignAsset(u32),
+}
+
+/// A position in a scalar market of Zeitgeist.
+#[cfg(feature = "zeitgeist")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ScalarPosition {
+    Long,
+    Short,
 }
@@ -0,0 +1,155 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/*
+
+The ERC-4626 vault in `base` built with the `psp22` feature, as a contract of its own.
+
+The vault's backend is chosen by cargo feature, but contracts are built without any, so this
+crate turns the feature on for its dependency. It adds no code: the vault's entry points are
+exported from `base`.
+
+It holds the end-to-end tests of PSP22 vaults, run against `psp22_token` and
+`reentrancy_attacker` on a node with contracts-pallet.
+
+*/
+
+pub use erc_4626::*;
+
+#[cfg(all(test, feature = "e2e-tests"))]
+mod e2e_tests {
+    use erc_4626::erc4626::{Erc4626Ref, Error};
+    use ink_e2e::build_message;
+    use psp22_token::{psp22_token::Psp22TokenRef, PSP22};
+    use reentrancy_attacker::reentrancy_attacker::ReentrancyAttackerRef;
+
+    type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+    #[ink_e2e::test]
+    async fn e2e_deposit_and_redeem_move_the_token(
+        mut client: ink_e2e::Client<C, E>,
+    ) -> E2EResult<()> {
+        // given
+        let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+        let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+        let constructor = Psp22TokenRef::new(1_000_000, Some(String::from("TKN")), 12);
+        let token = client
+            .instantiate("psp22_token", &ink_e2e::alice(), constructor, 0, None)
+            .await
+            .expect("instantiate failed")
+            .account_id;
+        let constructor = Erc4626Ref::new(token, 12, 0, 0);
+        let vault = client
+            .instantiate("psp22_vault", &ink_e2e::alice(), constructor, 0, None)
+            .await
+            .expect("instantiate failed")
+            .account_id;
+
+        let name = build_message::<Erc4626Ref>(vault).call(|vault| vault.name());
+        let name_res = client.call_dry_run(&ink_e2e::alice(), &name, 0, None).await;
+        assert_eq!(name_res.return_value(), "Vault TKN");
+
+        // when
+        // Without an approval the vault cannot pull the deposit.
+        let deposit = build_message::<Erc4626Ref>(vault).call(|vault| vault.deposit(1000, alice));
+        let deposit_res = client
+            .call_dry_run(&ink_e2e::alice(), &deposit, 0, None)
+            .await;
+        assert_eq!(deposit_res.return_value(), Err(Error::AssetTransferFailed));
+
+        let approve =
+            build_message::<Psp22TokenRef>(token).call(|token| token.approve(vault, 1000));
+        client
+            .call(&ink_e2e::alice(), approve, 0, None)
+            .await
+            .expect("approve failed");
+        let deposit_res = client
+            .call(&ink_e2e::alice(), deposit, 0, None)
+            .await
+            .expect("deposit failed");
+        assert_eq!(deposit_res.return_value(), Ok(1000));
+
+        let redeem = build_message::<Erc4626Ref>(vault).call(|vault| vault.redeem(400, bob, alice));
+        let redeem_res = client
+            .call(&ink_e2e::alice(), redeem, 0, None)
+            .await
+            .expect("redeem failed");
+        assert_eq!(redeem_res.return_value(), Ok(400));
+
+        // then
+        let vault_balance =
+            build_message::<Psp22TokenRef>(token).call(|token| token.balance_of(vault));
+        let vault_balance_res = client
+            .call_dry_run(&ink_e2e::alice(), &vault_balance, 0, None)
+            .await;
+        assert_eq!(vault_balance_res.return_value(), 600);
+
+        let bob_balance = build_message::<Psp22TokenRef>(token).call(|token| token.balance_of(bob));
+        let bob_balance_res = client
+            .call_dry_run(&ink_e2e::alice(), &bob_balance, 0, None)
+            .await;
+        assert_eq!(bob_balance_res.return_value(), 400);
+
+        let total_assets = build_message::<Erc4626Ref>(vault).call(|vault| vault.total_assets());
+        let total_assets_res = client
+            .call_dry_run(&ink_e2e::alice(), &total_assets, 0, None)
+            .await;
+        assert_eq!(total_assets_res.return_value(), Ok(600));
+
+        let shares = build_message::<Erc4626Ref>(vault).call(|vault| vault.balance_of(alice));
+        let shares_res = client
+            .call_dry_run(&ink_e2e::alice(), &shares, 0, None)
+            .await;
+        assert_eq!(shares_res.return_value(), 600);
+
+        Ok(())
+    }
+
+    #[ink_e2e::test]
+    async fn e2e_reentrant_deposit_is_rejected(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+        let constructor = ReentrancyAttackerRef::new();
+        let attacker = client
+            .instantiate(
+                "reentrancy_attacker",
+                &ink_e2e::alice(),
+                constructor,
+                0,
+                None,
+            )
+            .await
+            .expect("instantiate failed")
+            .account_id;
+        let constructor = Erc4626Ref::new(attacker, 12, 0, 0);
+        let vault = client
+            .instantiate("psp22_vault", &ink_e2e::alice(), constructor, 0, None)
+            .await
+            .expect("instantiate failed")
+            .account_id;
+        let set_vault = build_message::<ReentrancyAttackerRef>(attacker)
+            .call(|attacker| attacker.set_vault(vault));
+        client
+            .call(&ink_e2e::alice(), set_vault, 0, None)
+            .await
+            .expect("set_vault failed");
+
+        // when
+        // Pulling the deposit makes the attacker call `deposit` on the vault again.
+        let deposit = build_message::<Erc4626Ref>(vault).call(|vault| vault.deposit(100, alice));
+        let deposit_res = client
+            .call(&ink_e2e::alice(), deposit, 0, None)
+            .await
+            .expect("deposit failed");
+
+        // then
+        assert_eq!(deposit_res.return_value(), Ok(100));
+        let reentry_succeeded = build_message::<ReentrancyAttackerRef>(attacker)
+            .call(|attacker| attacker.reentry_succeeded());
+        let reentry_succeeded_res = client
+            .call_dry_run(&ink_e2e::alice(), &reentry_succeeded, 0, None)
+            .await;
+        assert_eq!(reentry_succeeded_res.return_value(), Some(false));
+
+        Ok(())
+    }
+}
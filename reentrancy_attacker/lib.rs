@@ -0,0 +1,159 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/*
+
+A malicious PSP22 token for testing the reentrancy lock of the ERC-4626 vault.
+
+Instantiate it, instantiate `psp22_vault` over this token, then call `set_vault` with the
+vault's address and `deposit` into the vault, as the end-to-end tests of `psp22_vault` do. When the
+vault pulls the deposit with `PSP22::transfer_from`, this token calls `deposit` on the vault
+again and records whether that nested call succeeded in `reentry_succeeded`.
+
+The vault's calls do not allow reentry, so the nested call is rejected by the runtime. The
+vault's own lock rejects it with `Reentrancy` if a backend ever allows reentry.
+
+*/
+
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+/// The errors of a PSP22 token.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 messages the vault calls on its underlying asset.
+#[ink::trait_definition]
+pub trait PSP22 {
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u128;
+
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: u128,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+}
+
+#[ink::contract]
+pub mod reentrancy_attacker {
+    use super::{PSP22Error, PSP22};
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+
+    /// A PSP22 token that calls back into the vault when it is pulled from.
+    #[ink(storage)]
+    pub struct ReentrancyAttacker {
+        /// The vault to call back into.
+        vault: Option<AccountId>,
+        /// Whether the last call back into the vault succeeded.
+        reentry_succeeded: Option<bool>,
+    }
+
+    impl ReentrancyAttacker {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                vault: None,
+                reentry_succeeded: None,
+            }
+        }
+
+        /// Sets the vault to call back into.
+        #[ink(message)]
+        pub fn set_vault(&mut self, vault: AccountId) {
+            self.vault = Some(vault);
+        }
+
+        /// Returns whether the last call back into the vault succeeded, or `None` if no
+        /// call was attempted yet.
+        #[ink(message)]
+        pub fn reentry_succeeded(&self) -> Option<bool> {
+            self.reentry_succeeded
+        }
+
+        /// Calls `deposit` on the vault and records whether it succeeded.
+        fn reenter(&mut self) {
+            let Some(vault) = self.vault else {
+                return;
+            };
+            // The vault's errors are all unit variants, so they decode as their index
+            let result = build_call::<Environment>()
+                .call(vault)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("deposit")))
+                        .push_arg(Balance::from(0_u8))
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<core::result::Result<Balance, u8>>()
+                .try_invoke();
+            self.reentry_succeeded = Some(matches!(result, Ok(Ok(Ok(_)))));
+        }
+    }
+
+    impl Default for ReentrancyAttacker {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl PSP22 for ReentrancyAttacker {
+        #[ink(message)]
+        fn balance_of(&self, _owner: AccountId) -> Balance {
+            0
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            _to: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            _from: AccountId,
+            _to: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            self.reenter();
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn no_reentry_without_vault() {
+            let mut attacker = ReentrancyAttacker::new();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(attacker.reentry_succeeded(), None);
+
+            assert_eq!(
+                attacker.transfer_from(accounts.alice, accounts.bob, 10, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(attacker.reentry_succeeded(), None);
+        }
+    }
+}